@@ -0,0 +1,351 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::core::{
+    http::{HTTPRequest, HTTPResponse, HttpMethod},
+    middleware_config::{
+        CacheKeepPolicy, CacheRequestKey, CacheResponseExpiration, MiddlewareCachingConfig,
+    },
+};
+
+/// Return "now" as a number of seconds since the Unix epoch.
+pub type NowFn = Arc<dyn Fn() -> i64 + Send + Sync>;
+
+/// Standards-compliant caching configuration derived from the response's own
+/// HTTP headers (RFC 7234 `Cache-Control` / `Expires`).
+///
+/// Unlike [`crate::common::simple_middleware_config::SimpleMiddlewareCachingConfig`],
+/// users don't supply `cache_keep`/`cache_response` closures: freshness is
+/// computed from the response instead. Keys are derived from the request method
+/// and URL; only safe, cacheable methods (`GET`, `HEAD`) are keyed.
+pub struct HttpSemanticsCachingConfig {
+    /// Whether this is a shared (proxy) cache. A shared cache must not store
+    /// `Cache-Control: private` responses.
+    pub shared: bool,
+
+    /// Fraction of the `Date - Last-Modified` interval used as the heuristic
+    /// freshness lifetime when no explicit lifetime is given. Defaults to `0.1`
+    /// (the 10% rule recommended by RFC 7234 §4.2.2).
+    pub heuristic_fraction: f64,
+
+    /// Upper bound, in seconds, on a heuristic freshness lifetime. Defaults to
+    /// 24 hours.
+    pub heuristic_max_lifetime: i64,
+
+    /// Return the current timestamp, in seconds since the Unix epoch.
+    pub now_fn: NowFn,
+}
+
+/// Default fraction of the `Date - Last-Modified` interval used as a heuristic
+/// freshness lifetime.
+const DEFAULT_HEURISTIC_FRACTION: f64 = 0.1;
+
+/// Default ceiling (24 hours) on a heuristic freshness lifetime.
+const DEFAULT_HEURISTIC_MAX_LIFETIME: i64 = 24 * 60 * 60;
+
+/// Status codes for which a heuristic freshness lifetime may be assigned
+/// (RFC 7231 §6.1).
+const HEURISTICALLY_CACHEABLE: [u16; 11] =
+    [200, 203, 204, 206, 300, 301, 404, 405, 410, 414, 501];
+
+impl HttpSemanticsCachingConfig {
+    /// Create a config for a private (per-user) cache.
+    #[inline]
+    pub fn private(now_fn: NowFn) -> Self {
+        Self {
+            shared: false,
+            heuristic_fraction: DEFAULT_HEURISTIC_FRACTION,
+            heuristic_max_lifetime: DEFAULT_HEURISTIC_MAX_LIFETIME,
+            now_fn,
+        }
+    }
+
+    /// Create a config for a shared (proxy) cache.
+    #[inline]
+    pub fn shared(now_fn: NowFn) -> Self {
+        Self {
+            shared: true,
+            heuristic_fraction: DEFAULT_HEURISTIC_FRACTION,
+            heuristic_max_lifetime: DEFAULT_HEURISTIC_MAX_LIFETIME,
+            now_fn,
+        }
+    }
+
+    /// Heuristic freshness lifetime derived from `Last-Modified`, if the
+    /// response is heuristically cacheable and carries both a `Date` and a
+    /// `Last-Modified` header.
+    fn heuristic_expiration(
+        &self,
+        response: &HTTPResponse<HashMap<String, Vec<String>>>,
+        call_timestamp: i64,
+    ) -> Option<i64> {
+        if !HEURISTICALLY_CACHEABLE.contains(&response.status) {
+            return None;
+        }
+        let date = header_value(&response.headers, "date").and_then(parse_http_date)?;
+        let last_modified =
+            header_value(&response.headers, "last-modified").and_then(parse_http_date)?;
+
+        let delta = date - last_modified;
+        if delta <= 0 {
+            return None;
+        }
+        let lifetime =
+            ((delta as f64 * self.heuristic_fraction) as i64).min(self.heuristic_max_lifetime);
+        Some(call_timestamp + lifetime)
+    }
+
+    /// Secondary cache key built from the values of the request headers named
+    /// in `vary`.
+    ///
+    /// Returns `None` when `vary` contains `*` (the response is uncacheable).
+    /// Field names are matched case-insensitively and the pairs are sorted so
+    /// the key is stable regardless of header ordering.
+    pub fn secondary_key(headers: &HashMap<String, Vec<String>>, vary: &[String]) -> Option<String> {
+        if vary.iter().any(|field| field == "*") {
+            return None;
+        }
+        let mut pairs: Vec<String> = vary
+            .iter()
+            .map(|field| {
+                let value = header_value(headers, field).unwrap_or_default();
+                format!("{field}={value}")
+            })
+            .collect();
+        pairs.sort();
+        Some(pairs.join("&"))
+    }
+}
+
+impl MiddlewareCachingConfig for HttpSemanticsCachingConfig {
+    type Headers = HashMap<String, Vec<String>>;
+    type CacheTime = i64;
+
+    fn key(&self, request: &HTTPRequest<Self::Headers>) -> CacheRequestKey {
+        match request.method {
+            HttpMethod::Get | HttpMethod::Head => {
+                CacheRequestKey::Key(format!("{} {}", method_token(&request.method), request.url))
+            }
+            _ => CacheRequestKey::NoKey,
+        }
+    }
+
+    fn cache_keep(
+        &self,
+        _request: &HTTPRequest<Self::Headers>,
+        _response: &HTTPResponse<Self::Headers>,
+        _call_timestamp: &Self::CacheTime,
+        expiration_time: &Option<Self::CacheTime>,
+    ) -> CacheKeepPolicy {
+        match expiration_time {
+            // Indefinitely fresh.
+            None => CacheKeepPolicy::Keep,
+            // Fresh while "now" hasn't reached the stored expiration date,
+            // otherwise the entry is stale and must be revalidated.
+            Some(expires) if (self.now_fn)() < *expires => CacheKeepPolicy::Keep,
+            Some(_) => CacheKeepPolicy::Update,
+        }
+    }
+
+    fn cache_response(
+        &self,
+        _request: &HTTPRequest<Self::Headers>,
+        response: &HTTPResponse<Self::Headers>,
+    ) -> Option<CacheResponseExpiration<Self::CacheTime>> {
+        let call_timestamp = (self.now_fn)();
+        let cache_control = parse_cache_control(&response.headers);
+
+        if cache_control.contains_key("no-store") {
+            return Some(CacheResponseExpiration::NoCache);
+        }
+        if self.shared && cache_control.contains_key("private") {
+            return Some(CacheResponseExpiration::NoCache);
+        }
+
+        if let Some(max_age) = cache_control.get("max-age").and_then(|v| v.as_deref()) {
+            if let Ok(seconds) = max_age.parse::<i64>() {
+                return Some(CacheResponseExpiration::CacheWithExpirationDate(
+                    call_timestamp + seconds,
+                ));
+            }
+        }
+
+        if let Some(expires) = header_value(&response.headers, "expires") {
+            if let Some(expires_at) = parse_http_date(expires) {
+                return Some(CacheResponseExpiration::CacheWithExpirationDate(expires_at));
+            }
+        }
+
+        if let Some(expires_at) = self.heuristic_expiration(response, call_timestamp) {
+            return Some(CacheResponseExpiration::CacheWithExpirationDate(expires_at));
+        }
+
+        // No freshness information at all: store the response but treat it as
+        // already stale (expiring at the moment it was received) so it is
+        // revalidated on the next use rather than served fresh forever.
+        Some(CacheResponseExpiration::CacheWithExpirationDate(
+            call_timestamp,
+        ))
+    }
+
+    fn revalidation_request(
+        &self,
+        request: &HTTPRequest<Self::Headers>,
+        stored_response: &HTTPResponse<Self::Headers>,
+    ) -> Option<HTTPRequest<Self::Headers>> {
+        let etag = header_value(&stored_response.headers, "etag");
+        let last_modified = header_value(&stored_response.headers, "last-modified");
+        if etag.is_none() && last_modified.is_none() {
+            return None;
+        }
+
+        let mut conditional = request.clone();
+        if let Some(etag) = etag {
+            set_header(&mut conditional.headers, "If-None-Match", etag);
+        }
+        if let Some(last_modified) = last_modified {
+            set_header(&mut conditional.headers, "If-Modified-Since", last_modified);
+        }
+        Some(conditional)
+    }
+
+    fn is_not_modified(&self, response: &HTTPResponse<Self::Headers>) -> bool {
+        response.status == 304
+    }
+
+    fn merge_not_modified(
+        &self,
+        stored: &HTTPResponse<Self::Headers>,
+        not_modified: &HTTPResponse<Self::Headers>,
+    ) -> HTTPResponse<Self::Headers> {
+        let mut refreshed = stored.clone();
+        for name in ["cache-control", "date", "expires", "etag", "last-modified"] {
+            if let Some(value) = header_value(&not_modified.headers, name) {
+                set_header(&mut refreshed.headers, name, value);
+            }
+        }
+        refreshed
+    }
+
+    fn vary(&self, response: &HTTPResponse<Self::Headers>) -> Vec<String> {
+        parse_vary(&response.headers)
+    }
+
+    fn tags(&self, response: &HTTPResponse<Self::Headers>) -> Vec<String> {
+        parse_tags(&response.headers)
+    }
+
+    fn vary_secondary_key(&self, headers: &Self::Headers, vary: &[String]) -> Option<String> {
+        Self::secondary_key(headers, vary)
+    }
+
+    fn vary_matches(
+        &self,
+        request: &HTTPRequest<Self::Headers>,
+        stored_request: &HTTPRequest<Self::Headers>,
+        vary: &[String],
+    ) -> bool {
+        Self::secondary_key(&request.headers, vary)
+            == Self::secondary_key(&stored_request.headers, vary)
+            && Self::secondary_key(&request.headers, vary).is_some()
+    }
+}
+
+/// Replace every case-insensitive occurrence of `name` with a single `value`.
+fn set_header(headers: &mut HashMap<String, Vec<String>>, name: &str, value: &str) {
+    headers.retain(|key, _| !key.eq_ignore_ascii_case(name));
+    headers.insert(name.to_owned(), vec![value.to_owned()]);
+}
+
+/// Canonical request-line token for a method.
+fn method_token(method: &HttpMethod) -> &str {
+    match method {
+        HttpMethod::Options => "OPTIONS",
+        HttpMethod::Get => "GET",
+        HttpMethod::Post => "POST",
+        HttpMethod::Put => "PUT",
+        HttpMethod::Delete => "DELETE",
+        HttpMethod::Head => "HEAD",
+        HttpMethod::Trace => "TRACE",
+        HttpMethod::Connect => "CONNECT",
+        HttpMethod::Patch => "PATCH",
+        HttpMethod::Custom(name) => name,
+    }
+}
+
+/// Look up the first value of a header by its case-insensitive name.
+pub(crate) fn header_value<'headers>(
+    headers: &'headers HashMap<String, Vec<String>>,
+    name: &str,
+) -> Option<&'headers str> {
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .and_then(|(_, values)| values.first())
+        .map(String::as_str)
+}
+
+/// Parse the `Cache-Control` header into a map of directive to optional value.
+/// Directive names are lower-cased; valueless directives map to `None`.
+pub(crate) fn parse_cache_control(
+    headers: &HashMap<String, Vec<String>>,
+) -> HashMap<String, Option<String>> {
+    let mut directives = HashMap::new();
+    let Some(value) = header_value(headers, "cache-control") else {
+        return directives;
+    };
+
+    for part in value.split(',') {
+        let part = part.trim();
+        if part.is_empty() {
+            continue;
+        }
+        let (name, value) = match part.split_once('=') {
+            Some((name, value)) => (name.trim(), Some(value.trim().trim_matches('"').to_owned())),
+            None => (part, None),
+        };
+        directives.insert(name.to_ascii_lowercase(), value);
+    }
+    directives
+}
+
+/// Parse the `Vary` header into a list of lower-cased field names. A `*` entry
+/// is returned as a single `"*"` element marking the response uncacheable.
+pub(crate) fn parse_vary(headers: &HashMap<String, Vec<String>>) -> Vec<String> {
+    let Some(value) = header_value(headers, "vary") else {
+        return Vec::new();
+    };
+    if value.split(',').any(|field| field.trim() == "*") {
+        return vec!["*".to_owned()];
+    }
+    value
+        .split(',')
+        .map(|field| field.trim().to_ascii_lowercase())
+        .filter(|field| !field.is_empty())
+        .collect()
+}
+
+/// Collect cache tags from the `Cache-Tag` and `Surrogate-Key` response
+/// headers. `Cache-Tag` values are comma-separated; `Surrogate-Key` values are
+/// space-separated (per the respective CDN conventions).
+pub(crate) fn parse_tags(headers: &HashMap<String, Vec<String>>) -> Vec<String> {
+    let mut tags = Vec::new();
+    if let Some(value) = header_value(headers, "cache-tag") {
+        tags.extend(value.split(',').map(str::trim).filter(|tag| !tag.is_empty()).map(str::to_owned));
+    }
+    if let Some(value) = header_value(headers, "surrogate-key") {
+        tags.extend(
+            value
+                .split_whitespace()
+                .map(str::to_owned),
+        );
+    }
+    tags
+}
+
+/// Parse an HTTP-date (RFC 1123 preferred form) into seconds since the Unix epoch.
+pub(crate) fn parse_http_date(value: &str) -> Option<i64> {
+    chrono::NaiveDateTime::parse_from_str(value.trim(), "%a, %d %b %Y %H:%M:%S GMT")
+        .ok()
+        .map(|date_time| date_time.and_utc().timestamp())
+}
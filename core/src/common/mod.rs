@@ -0,0 +1,2 @@
+pub mod http_semantics_config;
+pub mod simple_middleware_config;
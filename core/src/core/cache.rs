@@ -1,7 +1,10 @@
-use super::error::Result;
+use super::error::{Error, Result};
 use super::http::{HTTPRequest, HTTPResponse};
 
+pub mod backends;
+
 /// Data to be stored in cache.
+#[derive(Clone)]
 pub struct CacheData<Headers, CacheTime>
 where
     Headers: Clone + Send + Sync,
@@ -19,6 +22,15 @@ where
 
     /// HTTP Response data.
     pub http_response: HTTPResponse<Headers>,
+
+    /// Request header field names this response varies on (from `Vary`),
+    /// normalized to lower-case. Used to select the matching content-negotiated
+    /// variant on lookup. Empty when the response does not vary.
+    pub vary: Vec<String>,
+
+    /// Tags this entry belongs to, enabling bulk invalidation via
+    /// [`CacheManager::delete_by_tag`]. Empty when the entry is untagged.
+    pub tags: Vec<String>,
 }
 
 /// A trait providing methods for storing, reading, and removing cache records.
@@ -47,4 +59,17 @@ pub trait CacheManager: Send + Sync {
     ) -> impl core::future::Future<Output = Result<Option<CacheData<Self::Headers, Self::CacheTime>>>>
            + Send
            + Sync;
+
+    /// Evict every entry carrying `tag`, returning the number removed.
+    ///
+    /// Defaults to a not-supported error for backends that cannot enumerate
+    /// their entries; backends that can should override this.
+    fn delete_by_tag(
+        &self,
+        tag: &str,
+    ) -> impl core::future::Future<Output = Result<usize>> + Send + Sync {
+        let _ = tag;
+        // TODO: introduce a dedicated `Unsupported` error variant.
+        async { Err(Error::FIXME) }
+    }
 }
@@ -0,0 +1,487 @@
+//! Ready-made [`CacheManager`] implementations.
+//!
+//! Both backends treat an entry whose `expiration_time` has passed as absent
+//! on [`CacheManager::get`], so a stale record is never served even if a
+//! caching config would have kept it.
+
+use core::sync::atomic::{AtomicU64, Ordering};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use super::{CacheData, CacheManager};
+use crate::core::error::{Error, Result};
+use crate::core::http::{HTTPRequest, HTTPResponse};
+
+/// Return "now" in the same unit as the stored `expiration_time`.
+pub type NowFn<CacheTime> = Arc<dyn Fn() -> CacheTime + Send + Sync>;
+
+/// In-memory [`CacheManager`] backed by a concurrent map.
+///
+/// An optional `max_entries` bound enables LRU eviction: once the map is full,
+/// inserting a new key evicts the least-recently-used entry.
+pub struct InMemoryCacheManager<Headers, CacheTime>
+where
+    Headers: Clone + Send + Sync,
+    CacheTime: Clone + PartialOrd + Send + Sync,
+{
+    entries: Mutex<HashMap<String, Entry<Headers, CacheTime>>>,
+    max_entries: Option<usize>,
+    now_fn: NowFn<CacheTime>,
+    clock: AtomicU64,
+}
+
+/// A stored record together with its last-use tick for LRU accounting.
+struct Entry<Headers, CacheTime>
+where
+    Headers: Clone + Send + Sync,
+    CacheTime: Clone + PartialOrd + Send + Sync,
+{
+    data: CacheData<Headers, CacheTime>,
+    last_used: u64,
+}
+
+impl<Headers, CacheTime> InMemoryCacheManager<Headers, CacheTime>
+where
+    Headers: Clone + Send + Sync,
+    CacheTime: Clone + PartialOrd + Send + Sync,
+{
+    /// Create an unbounded in-memory cache.
+    pub fn new(now_fn: NowFn<CacheTime>) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            max_entries: None,
+            now_fn,
+            clock: AtomicU64::new(0),
+        }
+    }
+
+    /// Create an in-memory cache that keeps at most `max_entries` records,
+    /// evicting the least-recently-used one when full.
+    pub fn with_capacity(now_fn: NowFn<CacheTime>, max_entries: usize) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            max_entries: Some(max_entries),
+            now_fn,
+            clock: AtomicU64::new(0),
+        }
+    }
+
+    fn next_tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn get_sync(&self, cache_key: &str) -> Option<CacheData<Headers, CacheTime>> {
+        let now = (self.now_fn)();
+        // TODO: proper error handling on a poisoned lock.
+        let mut entries = self.entries.lock().ok()?;
+        let entry = entries.get_mut(cache_key)?;
+        if is_expired(&entry.data.expiration_time, &now) {
+            entries.remove(cache_key);
+            return None;
+        }
+        entry.last_used = self.next_tick();
+        Some(entry.data.clone())
+    }
+
+    fn put_sync(&self, cache_key: &str, data: &CacheData<Headers, CacheTime>) {
+        let tick = self.next_tick();
+        let Ok(mut entries) = self.entries.lock() else {
+            return;
+        };
+        if let Some(max_entries) = self.max_entries {
+            if !entries.contains_key(cache_key) && entries.len() >= max_entries {
+                if let Some(victim) = entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.last_used)
+                    .map(|(key, _)| key.clone())
+                {
+                    entries.remove(&victim);
+                }
+            }
+        }
+        entries.insert(
+            cache_key.to_owned(),
+            Entry {
+                data: data.clone(),
+                last_used: tick,
+            },
+        );
+    }
+
+    fn delete_sync(&self, cache_key: &str) -> Option<CacheData<Headers, CacheTime>> {
+        let mut entries = self.entries.lock().ok()?;
+        entries.remove(cache_key).map(|entry| entry.data)
+    }
+
+    fn delete_by_tag_sync(&self, tag: &str) -> usize {
+        let Ok(mut entries) = self.entries.lock() else {
+            return 0;
+        };
+        let before = entries.len();
+        entries.retain(|_, entry| !entry.data.tags.iter().any(|stored| stored == tag));
+        before - entries.len()
+    }
+}
+
+impl<Headers, CacheTime> CacheManager for InMemoryCacheManager<Headers, CacheTime>
+where
+    Headers: Clone + Send + Sync,
+    CacheTime: Clone + PartialOrd + Send + Sync,
+{
+    type Headers = Headers;
+    type CacheTime = CacheTime;
+
+    fn get(
+        &self,
+        cache_key: &str,
+    ) -> impl core::future::Future<Output = Result<Option<CacheData<Headers, CacheTime>>>> + Send + Sync
+    {
+        let data = self.get_sync(cache_key);
+        async move { Ok(data) }
+    }
+
+    fn put(
+        &self,
+        cache_key: &str,
+        data: &CacheData<Headers, CacheTime>,
+    ) -> impl core::future::Future<Output = Result<()>> + Send + Sync {
+        self.put_sync(cache_key, data);
+        async move { Ok(()) }
+    }
+
+    fn delete(
+        &self,
+        cache_key: &str,
+    ) -> impl core::future::Future<Output = Result<Option<CacheData<Headers, CacheTime>>>> + Send + Sync
+    {
+        let data = self.delete_sync(cache_key);
+        async move { Ok(data) }
+    }
+
+    fn delete_by_tag(
+        &self,
+        tag: &str,
+    ) -> impl core::future::Future<Output = Result<usize>> + Send + Sync {
+        let removed = self.delete_by_tag_sync(tag);
+        async move { Ok(removed) }
+    }
+}
+
+/// On-disk [`CacheManager`] that serializes records to a directory.
+///
+/// Each entry lives under a content-addressed directory derived from the cache
+/// key. Metadata (request, response head, timestamps) is written to `meta.json`
+/// and the response body to `body.bin`, so large bodies stay out of the
+/// metadata blob and can be streamed independently.
+pub struct DiskCacheManager {
+    root: PathBuf,
+    now_fn: NowFn<i64>,
+}
+
+/// Header representation shared by the on-disk request and response records.
+type DiskHeaders = HashMap<String, Vec<String>>;
+
+/// Serializable metadata written alongside the body blob.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct StoredMeta {
+    call_timestamp: i64,
+    expiration_time: Option<i64>,
+    http_request: HTTPRequest<DiskHeaders>,
+    /// Response with an empty body; the bytes live in `body.bin`.
+    http_response: HTTPResponse<DiskHeaders>,
+    #[serde(default)]
+    vary: Vec<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+impl DiskCacheManager {
+    /// Create a disk cache rooted at `root`, creating the directory if needed.
+    pub fn new(root: impl Into<PathBuf>, now_fn: NowFn<i64>) -> Result<Self> {
+        let root = root.into();
+        std::fs::create_dir_all(&root).map_err(|_| Error::FIXME)?;
+        Ok(Self { root, now_fn })
+    }
+
+    /// Directory holding the record for `cache_key`.
+    fn entry_dir(&self, cache_key: &str) -> PathBuf {
+        let mut hasher = DefaultHasher::new();
+        cache_key.hash(&mut hasher);
+        let digest = format!("{:016x}", hasher.finish());
+        self.root.join(&digest[..2]).join(&digest[2..])
+    }
+
+    fn read_sync(&self, cache_key: &str) -> Result<Option<CacheData<DiskHeaders, i64>>> {
+        let dir = self.entry_dir(cache_key);
+        let meta_path = dir.join("meta.json");
+        if !meta_path.exists() {
+            return Ok(None);
+        }
+
+        let meta_bytes = std::fs::read(&meta_path).map_err(|_| Error::FIXME)?;
+        let meta: StoredMeta = serde_json::from_slice(&meta_bytes).map_err(|_| Error::FIXME)?;
+
+        let now = (self.now_fn)();
+        if is_expired(&meta.expiration_time, &now) {
+            // Lazily drop expired records so they don't linger on disk.
+            let _ = std::fs::remove_dir_all(&dir);
+            return Ok(None);
+        }
+
+        let body = std::fs::read(dir.join("body.bin")).unwrap_or_default();
+        let http_response = HTTPResponse {
+            body,
+            ..meta.http_response
+        };
+        Ok(Some(CacheData {
+            call_timestamp: meta.call_timestamp,
+            expiration_time: meta.expiration_time,
+            http_request: meta.http_request,
+            http_response,
+            vary: meta.vary,
+            tags: meta.tags,
+        }))
+    }
+
+    fn write_sync(&self, cache_key: &str, data: &CacheData<DiskHeaders, i64>) -> Result<()> {
+        let dir = self.entry_dir(cache_key);
+        std::fs::create_dir_all(&dir).map_err(|_| Error::FIXME)?;
+
+        let head = HTTPResponse {
+            body: Vec::new(),
+            ..data.http_response.clone()
+        };
+        let meta = StoredMeta {
+            call_timestamp: data.call_timestamp,
+            expiration_time: data.expiration_time,
+            http_request: data.http_request.clone(),
+            http_response: head,
+            vary: data.vary.clone(),
+            tags: data.tags.clone(),
+        };
+        let meta_bytes = serde_json::to_vec(&meta).map_err(|_| Error::FIXME)?;
+
+        write_atomic(&dir.join("body.bin"), &data.http_response.body)?;
+        write_atomic(&dir.join("meta.json"), &meta_bytes)?;
+        Ok(())
+    }
+
+    fn remove_sync(&self, cache_key: &str) -> Result<Option<CacheData<DiskHeaders, i64>>> {
+        let existing = self.read_sync(cache_key)?;
+        if existing.is_some() {
+            let _ = std::fs::remove_dir_all(self.entry_dir(cache_key));
+        }
+        Ok(existing)
+    }
+
+    fn purge_by_tag_sync(&self, tag: &str) -> usize {
+        let mut removed = 0;
+        let Ok(shards) = std::fs::read_dir(&self.root) else {
+            return 0;
+        };
+        for shard in shards.flatten() {
+            let Ok(entries) = std::fs::read_dir(shard.path()) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let dir = entry.path();
+                let Ok(bytes) = std::fs::read(dir.join("meta.json")) else {
+                    continue;
+                };
+                let Ok(meta) = serde_json::from_slice::<StoredMeta>(&bytes) else {
+                    continue;
+                };
+                if meta.tags.iter().any(|stored| stored == tag)
+                    && std::fs::remove_dir_all(&dir).is_ok()
+                {
+                    removed += 1;
+                }
+            }
+        }
+        removed
+    }
+}
+
+impl CacheManager for DiskCacheManager {
+    type Headers = DiskHeaders;
+    type CacheTime = i64;
+
+    fn get(
+        &self,
+        cache_key: &str,
+    ) -> impl core::future::Future<Output = Result<Option<CacheData<DiskHeaders, i64>>>> + Send + Sync
+    {
+        let result = self.read_sync(cache_key);
+        async move { result }
+    }
+
+    fn put(
+        &self,
+        cache_key: &str,
+        data: &CacheData<DiskHeaders, i64>,
+    ) -> impl core::future::Future<Output = Result<()>> + Send + Sync {
+        let result = self.write_sync(cache_key, data);
+        async move { result }
+    }
+
+    fn delete(
+        &self,
+        cache_key: &str,
+    ) -> impl core::future::Future<Output = Result<Option<CacheData<DiskHeaders, i64>>>> + Send + Sync
+    {
+        let result = self.remove_sync(cache_key);
+        async move { result }
+    }
+
+    fn delete_by_tag(
+        &self,
+        tag: &str,
+    ) -> impl core::future::Future<Output = Result<usize>> + Send + Sync {
+        let removed = self.purge_by_tag_sync(tag);
+        async move { Ok(removed) }
+    }
+}
+
+/// Whether an entry with the given expiration is expired at `now`.
+fn is_expired<CacheTime: PartialOrd>(expiration_time: &Option<CacheTime>, now: &CacheTime) -> bool {
+    matches!(expiration_time, Some(expires) if expires <= now)
+}
+
+/// Write `bytes` to `path` via a temporary file and rename, so a reader never
+/// observes a half-written blob.
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, bytes).map_err(|_| Error::FIXME)?;
+    std::fs::rename(&tmp, path).map_err(|_| Error::FIXME)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::http::{HttpMethod, HttpVersion};
+    use futures::executor::block_on;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    /// A `now_fn` that always reports the same instant, so expiry is deterministic.
+    fn fixed_now(now: i64) -> NowFn<i64> {
+        Arc::new(move || now)
+    }
+
+    fn entry(tags: &[&str], expiration_time: Option<i64>) -> CacheData<DiskHeaders, i64> {
+        let url = url::Url::parse("https://example.com/resource").unwrap();
+        CacheData {
+            call_timestamp: 0,
+            expiration_time,
+            http_request: HTTPRequest {
+                method: HttpMethod::Get,
+                url: url.clone(),
+                headers: HashMap::new(),
+                body: Vec::new(),
+            },
+            http_response: HTTPResponse {
+                version: HttpVersion::Http11,
+                url,
+                status: 200,
+                reason: "OK".to_owned(),
+                headers: HashMap::new(),
+                body: b"payload".to_vec(),
+            },
+            vary: Vec::new(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        }
+    }
+
+    fn scratch_dir() -> PathBuf {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let unique = format!(
+            "http-caching-test-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::SeqCst)
+        );
+        std::env::temp_dir().join(unique)
+    }
+
+    #[test]
+    fn in_memory_round_trip() {
+        let cache = InMemoryCacheManager::<DiskHeaders, i64>::new(fixed_now(1000));
+        block_on(cache.put("k", &entry(&[], None))).unwrap();
+        let stored = block_on(cache.get("k")).unwrap().expect("entry present");
+        assert_eq!(stored.http_response.body, b"payload");
+    }
+
+    #[test]
+    fn in_memory_treats_expired_entry_as_absent() {
+        let cache = InMemoryCacheManager::<DiskHeaders, i64>::new(fixed_now(1000));
+        block_on(cache.put("k", &entry(&[], Some(500)))).unwrap();
+        assert!(block_on(cache.get("k")).unwrap().is_none());
+    }
+
+    #[test]
+    fn in_memory_evicts_least_recently_used() {
+        let cache = InMemoryCacheManager::<DiskHeaders, i64>::with_capacity(fixed_now(1000), 2);
+        block_on(cache.put("a", &entry(&[], None))).unwrap();
+        block_on(cache.put("b", &entry(&[], None))).unwrap();
+        // Touch `a` so `b` becomes the least-recently-used victim.
+        block_on(cache.get("a")).unwrap();
+        block_on(cache.put("c", &entry(&[], None))).unwrap();
+
+        assert!(block_on(cache.get("a")).unwrap().is_some());
+        assert!(block_on(cache.get("b")).unwrap().is_none());
+        assert!(block_on(cache.get("c")).unwrap().is_some());
+    }
+
+    #[test]
+    fn in_memory_deletes_by_tag() {
+        let cache = InMemoryCacheManager::<DiskHeaders, i64>::new(fixed_now(1000));
+        block_on(cache.put("a", &entry(&["news"], None))).unwrap();
+        block_on(cache.put("b", &entry(&["news", "sports"], None))).unwrap();
+        block_on(cache.put("c", &entry(&["sports"], None))).unwrap();
+
+        assert_eq!(block_on(cache.delete_by_tag("news")).unwrap(), 2);
+        assert!(block_on(cache.get("a")).unwrap().is_none());
+        assert!(block_on(cache.get("c")).unwrap().is_some());
+    }
+
+    #[test]
+    fn disk_round_trip_keeps_body_and_head_separate() {
+        let root = scratch_dir();
+        let cache = DiskCacheManager::new(&root, fixed_now(1000)).unwrap();
+        block_on(cache.put("k", &entry(&[], None))).unwrap();
+
+        let stored = block_on(cache.get("k")).unwrap().expect("entry present");
+        assert_eq!(stored.http_response.body, b"payload");
+        assert_eq!(stored.http_response.status, 200);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn disk_drops_expired_entry_on_get() {
+        let root = scratch_dir();
+        let cache = DiskCacheManager::new(&root, fixed_now(1000)).unwrap();
+        block_on(cache.put("k", &entry(&[], Some(500)))).unwrap();
+
+        assert!(block_on(cache.get("k")).unwrap().is_none());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn disk_deletes_and_purges_by_tag() {
+        let root = scratch_dir();
+        let cache = DiskCacheManager::new(&root, fixed_now(1000)).unwrap();
+        block_on(cache.put("a", &entry(&["news"], None))).unwrap();
+        block_on(cache.put("b", &entry(&["news"], None))).unwrap();
+
+        assert!(block_on(cache.delete("a")).unwrap().is_some());
+        assert!(block_on(cache.get("a")).unwrap().is_none());
+        assert_eq!(block_on(cache.delete_by_tag("news")).unwrap(), 1);
+        assert!(block_on(cache.get("b")).unwrap().is_none());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
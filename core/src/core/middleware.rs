@@ -19,8 +19,31 @@ pub enum CacheHitResult {
     CacheHit,
     /// Cache hit, data has been updated from remote
     CacheUpdate,
+    /// Cache hit, a stale entry was revalidated via a `304 Not Modified` response
+    CacheRevalidated,
     /// Cache hit, cached data has been evicted, data has been retrieved from remote
     CacheEvict,
+    /// Cache miss under [`CacheMode::OnlyIfCached`]; the network was not used (504-style)
+    CacheGatewayTimeout,
+}
+
+/// Per-request override of the overall caching strategy, modelled on the
+/// request modes exposed by browser/fetch-style caches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CacheMode {
+    /// Consult the caching config and cache as usual.
+    #[default]
+    Default,
+    /// Bypass the cache entirely: never read and never write.
+    NoStore,
+    /// Always fetch from remote, but still store the result.
+    Reload,
+    /// Always revalidate a stored entry before serving it.
+    NoCache,
+    /// Serve any stored entry regardless of staleness; only fetch on a true miss.
+    ForceCache,
+    /// Serve the stored entry if present, otherwise fail without touching the network.
+    OnlyIfCached,
 }
 
 pub async fn handle_response_caching<
@@ -36,6 +59,7 @@ pub async fn handle_response_caching<
     cache_manager: &'src MiddlewareCacheManager,
     middleware_caching_config: &'src MiddlewareCachingConfig,
     current_time_fn: &'src CurrentTimeFn<CacheTime>,
+    cache_mode: CacheMode,
 ) -> Result<(Option<HTTPResponse<Headers>>, CacheHitResult)>
 where
     CacheTime: Send + Sync,
@@ -46,13 +70,70 @@ where
         + Sync,
     RequestCaller: middleware_config::RequestCaller<Headers = Headers>,
 {
+    // `NoStore` never touches the cache in either direction.
+    if matches!(cache_mode, CacheMode::NoStore) {
+        let remote_response = request_caller.read_remote_headers(request).await?;
+        let remote_response_with_body = HTTPResponse {
+            version: remote_response.version(),
+            status: remote_response.status(),
+            reason: remote_response.reason(),
+            url: remote_response.url(),
+            headers: remote_response.headers().clone(),
+            body: remote_response.body().await?,
+        };
+        return Ok((Some(remote_response_with_body), CacheHitResult::CacheOff));
+    }
+
     let middleware_config::CacheRequestKey::Key(cache_key) = middleware_caching_config.key(request)
     else {
         return Ok((None, CacheHitResult::CacheOff));
     };
 
     // TODO: proper error handling on await
-    let cache_data_opt = cache_manager.get(&cache_key).await?;
+    // The base key identifies *a* stored variant, which carries the response's
+    // `Vary` field names. When the response varies, re-key the lookup by this
+    // request's values for those fields so two content-negotiated variants of
+    // the same resource are served from their own entries instead of
+    // overwriting one another.
+    let cache_data_opt = match cache_manager.get(&cache_key).await? {
+        Some(probe) if !probe.vary.is_empty() => {
+            match middleware_caching_config.vary_secondary_key(&request.headers, &probe.vary) {
+                // `Vary: *` marks the response uncacheable: never a hit.
+                None => None,
+                // The probe is already the variant this request asked for.
+                Some(secondary)
+                    if middleware_caching_config
+                        .vary_secondary_key(&probe.http_request.headers, &probe.vary)
+                        .as_deref()
+                        == Some(secondary.as_str()) =>
+                {
+                    Some(probe)
+                }
+                // A different variant: look it up under its secondary key.
+                Some(secondary) => {
+                    cache_manager
+                        .get(&vary_variant_key(&cache_key, &secondary))
+                        .await?
+                }
+            }
+        }
+        other => other,
+    };
+
+    // A stored variant whose varied request headers differ from this request
+    // must not be served (content-negotiation mismatch counts as a miss).
+    let cache_data_opt = match cache_data_opt {
+        Some(cache_data)
+            if !middleware_caching_config.vary_matches(
+                request,
+                &cache_data.http_request,
+                &cache_data.vary,
+            ) =>
+        {
+            None
+        }
+        other => other,
+    };
 
     let cache_keep = cache_data_opt.as_ref().map(|cache_data| {
         middleware_caching_config.cache_keep(
@@ -63,28 +144,76 @@ where
         )
     });
 
-    match cache_keep {
-        Some(CacheKeepPolicy::Skip) => {
-            return Ok((None, CacheHitResult::CacheOff));
+    let had_cached = cache_data_opt.is_some();
+
+    match cache_mode {
+        // Serve any stored entry regardless of staleness, only fetch on a miss.
+        CacheMode::ForceCache => {
+            if let Some(cached_data) = &cache_data_opt {
+                return Ok((
+                    Some(cached_data.http_response.clone()),
+                    CacheHitResult::CacheHit,
+                ));
+            }
         }
-        Some(CacheKeepPolicy::Keep) => {
-            let Some(cached_data) = cache_data_opt else {
-                return Err(Error::FIXME);
+        // Never hit the network: serve the stored entry or fail 504-style.
+        CacheMode::OnlyIfCached => {
+            return match &cache_data_opt {
+                Some(cached_data) => Ok((
+                    Some(cached_data.http_response.clone()),
+                    CacheHitResult::CacheHit,
+                )),
+                None => Ok((None, CacheHitResult::CacheGatewayTimeout)),
             };
-            return Ok((Some(cached_data.http_response), CacheHitResult::CacheHit));
         }
-        Some(CacheKeepPolicy::Evict) => {
-            // TODO: proper error handling on await
-            cache_manager.delete(&cache_key).await?;
-            return Ok((None, CacheHitResult::CacheEvict));
-        }
-        // cache data needs to be updated or there's a cache miss => process later
-        Some(CacheKeepPolicy::Update) | None => {}
+        // Honour the config's keep policy.
+        CacheMode::Default => match cache_keep {
+            Some(CacheKeepPolicy::Skip) => {
+                return Ok((None, CacheHitResult::CacheOff));
+            }
+            Some(CacheKeepPolicy::Keep) => {
+                let Some(cached_data) = &cache_data_opt else {
+                    return Err(Error::FIXME);
+                };
+                return Ok((
+                    Some(cached_data.http_response.clone()),
+                    CacheHitResult::CacheHit,
+                ));
+            }
+            Some(CacheKeepPolicy::Evict) => {
+                // TODO: proper error handling on await
+                cache_manager.delete(&cache_key).await?;
+                return Ok((None, CacheHitResult::CacheEvict));
+            }
+            // cache data needs to be updated or there's a cache miss => process later
+            Some(CacheKeepPolicy::Update) | None => {}
+        },
+        // `Reload`/`NoCache` always go to the remote; `NoStore` handled above.
+        CacheMode::Reload | CacheMode::NoCache | CacheMode::NoStore => {}
     }
 
+    // A stale entry is revalidated conditionally; a true miss is fetched in full.
+    // `NoCache` forces revalidation of any stored entry, while `Reload` always
+    // performs an unconditional full fetch.
+    let revalidating = match cache_mode {
+        CacheMode::NoCache => had_cached,
+        CacheMode::Reload => false,
+        _ => matches!(cache_keep, Some(CacheKeepPolicy::Update)),
+    };
+    let conditional_request = if revalidating {
+        cache_data_opt
+            .as_ref()
+            .and_then(|cache_data| {
+                middleware_caching_config.revalidation_request(request, &cache_data.http_response)
+            })
+    } else {
+        None
+    };
+    let outgoing_request = conditional_request.as_ref().unwrap_or(request);
+
     // Cache miss
     // TODO: proper error handling on await
-    let remote_response = request_caller.read_remote_headers(request).await?;
+    let remote_response = request_caller.read_remote_headers(outgoing_request).await?;
 
     let remote_response_no_body = HTTPResponse {
         version: remote_response.version(),
@@ -95,6 +224,59 @@ where
         body: vec![],
     };
 
+    // Revalidation hit: the stored body is still valid, only the freshness
+    // metadata is refreshed from the `304` response.
+    if revalidating && middleware_caching_config.is_not_modified(&remote_response_no_body) {
+        let Some(cached_data) = cache_data_opt else {
+            return Err(Error::FIXME);
+        };
+        let refreshed_response = middleware_caching_config
+            .merge_not_modified(&cached_data.http_response, &remote_response_no_body);
+
+        let expiration_time = match middleware_caching_config
+            .cache_response(request, &refreshed_response)
+            .unwrap_or(CacheResponseExpiration::<CacheTime>::NoCache)
+        {
+            // The refreshed response must not be stored: the cached entry is
+            // left as is.
+            CacheResponseExpiration::NoCache => {
+                return Ok((Some(refreshed_response), CacheHitResult::CacheRevalidated));
+            }
+            CacheResponseExpiration::CacheWithoutExpirationDate => None,
+            CacheResponseExpiration::CacheWithExpirationDate(expiration_date) => {
+                Some(expiration_date)
+            }
+        };
+
+        let vary = middleware_caching_config.vary(&refreshed_response);
+        let tags = middleware_caching_config.tags(&refreshed_response);
+        let refreshed_cache_data = CacheData::<Headers, CacheTime> {
+            call_timestamp: current_time_fn(),
+            expiration_time,
+            http_request: HTTPRequest::new(request),
+            http_response: refreshed_response,
+            vary,
+            tags,
+        };
+
+        // TODO: proper error handling on await
+        cache_manager.put(&cache_key, &refreshed_cache_data).await?;
+        if !refreshed_cache_data.vary.is_empty() {
+            if let Some(secondary) = middleware_caching_config
+                .vary_secondary_key(&request.headers, &refreshed_cache_data.vary)
+            {
+                cache_manager
+                    .put(&vary_variant_key(&cache_key, &secondary), &refreshed_cache_data)
+                    .await?;
+            }
+        }
+
+        return Ok((
+            Some(refreshed_cache_data.http_response),
+            CacheHitResult::CacheRevalidated,
+        ));
+    }
+
     let cache_policy = middleware_caching_config
         .cache_response(request, &remote_response_no_body)
         .unwrap_or(CacheResponseExpiration::<CacheTime>::NoCache);
@@ -120,17 +302,30 @@ where
         CacheResponseExpiration::CacheWithExpirationDate(expiration_date) => Some(expiration_date),
     };
     let call_timestamp = current_time_fn();
+    let vary = middleware_caching_config.vary(&remote_response_with_body);
+    let tags = middleware_caching_config.tags(&remote_response_with_body);
     let new_cache_data = CacheData::<Headers, CacheTime> {
         call_timestamp,
         expiration_time,
         http_request: HTTPRequest::new(request),
         http_response: remote_response_with_body.clone(),
+        vary,
+        tags,
     };
 
     // TODO: proper error handling on await
     cache_manager.put(&cache_key, &new_cache_data).await?;
+    if !new_cache_data.vary.is_empty() {
+        if let Some(secondary) =
+            middleware_caching_config.vary_secondary_key(&request.headers, &new_cache_data.vary)
+        {
+            cache_manager
+                .put(&vary_variant_key(&cache_key, &secondary), &new_cache_data)
+                .await?;
+        }
+    }
 
-    let cache_hit_result = if matches!(cache_keep, Some(CacheKeepPolicy::Update)) {
+    let cache_hit_result = if had_cached {
         CacheHitResult::CacheUpdate
     } else {
         CacheHitResult::CacheMiss
@@ -138,3 +333,12 @@ where
 
     Ok((Some(remote_response_with_body), cache_hit_result))
 }
+
+/// Compose the storage key for a content-negotiated variant from the base
+/// request key and the response's `Vary`-derived secondary key.
+///
+/// The separator is a control byte that cannot appear in a user-facing key, so
+/// a variant key never collides with a base key.
+fn vary_variant_key(cache_key: &str, secondary: &str) -> String {
+    format!("{cache_key}\u{1}{secondary}")
+}
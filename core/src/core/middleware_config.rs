@@ -69,6 +69,87 @@ pub trait MiddlewareCachingConfig {
         request: &HTTPRequest<Self::Headers>,
         response: &HTTPResponse<Self::Headers>,
     ) -> Option<CacheResponseExpiration<Self::CacheTime>>;
+
+    /// Build a conditional request used to revalidate a stale entry.
+    ///
+    /// Called when a stored entry is stale ([`CacheKeepPolicy::Update`]). The
+    /// implementation may attach `If-None-Match` / `If-Modified-Since` derived
+    /// from the stored response's validators; returning `None` falls back to an
+    /// unconditional re-fetch.
+    fn revalidation_request(
+        &self,
+        request: &HTTPRequest<Self::Headers>,
+        stored_response: &HTTPResponse<Self::Headers>,
+    ) -> Option<HTTPRequest<Self::Headers>> {
+        let _ = (request, stored_response);
+        None
+    }
+
+    /// Whether a freshly fetched response is a `304 Not Modified` revalidation hit.
+    fn is_not_modified(&self, response: &HTTPResponse<Self::Headers>) -> bool {
+        let _ = response;
+        false
+    }
+
+    /// Merge the headers carried by a `304 Not Modified` response onto the
+    /// stored response, returning the refreshed response to re-store.
+    ///
+    /// A `304` carries no body, so the stored body is retained.
+    fn merge_not_modified(
+        &self,
+        stored: &HTTPResponse<Self::Headers>,
+        not_modified: &HTTPResponse<Self::Headers>,
+    ) -> HTTPResponse<Self::Headers> {
+        let _ = not_modified;
+        stored.clone()
+    }
+
+    /// Field names listed in the response's `Vary` header, normalized to
+    /// lower-case, to record alongside the stored entry.
+    ///
+    /// An empty result means the response does not vary; a single `"*"` entry
+    /// marks the response as uncacheable.
+    fn vary(&self, response: &HTTPResponse<Self::Headers>) -> Vec<String> {
+        let _ = response;
+        Vec::new()
+    }
+
+    /// Secondary cache key built from this request's values for the `Vary`
+    /// fields, distinguishing content-negotiated variants of one resource.
+    ///
+    /// Returns `None` when the response is uncacheable under `vary` (e.g. a
+    /// `Vary: *` entry). The default returns `None`, meaning the config does not
+    /// distinguish variants; configs that honour `Vary` override it.
+    fn vary_secondary_key(&self, headers: &Self::Headers, vary: &[String]) -> Option<String> {
+        let _ = (headers, vary);
+        None
+    }
+
+    /// Whether a stored variant matches the current request under its recorded
+    /// `Vary` fields.
+    ///
+    /// Returns `false` on a secondary-key mismatch or when the stored entry
+    /// varies on `*`, so the caller treats it as a miss rather than serving the
+    /// wrong content-negotiated variant. The default only checks for `*` and
+    /// does not compare request headers; configs that honour `Vary` override it.
+    fn vary_matches(
+        &self,
+        request: &HTTPRequest<Self::Headers>,
+        stored_request: &HTTPRequest<Self::Headers>,
+        vary: &[String],
+    ) -> bool {
+        let _ = (request, stored_request);
+        !vary.iter().any(|field| field == "*")
+    }
+
+    /// Tags to associate with a stored entry, enabling bulk invalidation.
+    ///
+    /// Typically derived from a `Cache-Tag` / `Surrogate-Key` response header,
+    /// but an implementation may compute them however it likes.
+    fn tags(&self, response: &HTTPResponse<Self::Headers>) -> Vec<String> {
+        let _ = response;
+        Vec::new()
+    }
 }
 
 /// Abstraction to do remote call for given request.
@@ -4,6 +4,7 @@ pub mod cache;
 pub mod http;
 pub mod middleware;
 pub mod middleware_config;
+pub mod single_flight;
 
 pub use error::Error;
 pub use error::Result;
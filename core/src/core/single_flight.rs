@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use futures::future::{BoxFuture, FutureExt, Shared};
+
+use super::error::Result;
+use super::http::{HTTPRequest, HTTPResponse, HttpResponse};
+use super::middleware_config::RequestCaller;
+
+/// A single-flight registry that coalesces concurrent work sharing a key.
+///
+/// When several callers ask for the same key while a call is already in
+/// progress, only the first one runs the underlying future; the rest await a
+/// shared clone of its result. This collapses a thundering herd of identical
+/// cache misses into a single remote call.
+///
+/// The pending future is registered *before* it is awaited, so late-arriving
+/// callers that start while the body is still downloading join the in-flight
+/// call instead of issuing their own. Once the leader completes, the entry is
+/// dropped and subsequent callers run afresh (hitting the now-populated cache).
+pub struct SingleFlight<V>
+where
+    V: Clone + Send + 'static,
+{
+    in_flight: Mutex<HashMap<String, Shared<BoxFuture<'static, V>>>>,
+}
+
+impl<V> SingleFlight<V>
+where
+    V: Clone + Send + 'static,
+{
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self {
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Run `make` under single-flight semantics for `key`.
+    ///
+    /// If a call for `key` is already in flight its result is shared; otherwise
+    /// `make` is invoked to produce the future that every concurrent caller
+    /// awaits. The result type must be `Clone` so it can be handed to every
+    /// waiter — wrap non-cloneable results (e.g. errors) in an `Arc`.
+    pub async fn run<MakeFut, Fut>(&self, key: &str, make: MakeFut) -> V
+    where
+        MakeFut: FnOnce() -> Fut,
+        Fut: core::future::Future<Output = V> + Send + 'static,
+    {
+        let (shared, leader) = {
+            // TODO: proper error handling on a poisoned lock.
+            let mut in_flight = self.in_flight.lock().expect("single-flight registry poisoned");
+            match in_flight.get(key) {
+                Some(existing) => (existing.clone(), false),
+                None => {
+                    let shared = make().boxed().shared();
+                    in_flight.insert(key.to_owned(), shared.clone());
+                    (shared, true)
+                }
+            }
+        };
+
+        let value = shared.await;
+
+        // The leader that registered the entry clears it once resolved so that
+        // later callers re-run rather than observe a completed future forever.
+        if leader {
+            self.in_flight
+                .lock()
+                .expect("single-flight registry poisoned")
+                .remove(key);
+        }
+
+        value
+    }
+}
+
+impl<V> Default for SingleFlight<V>
+where
+    V: Clone + Send + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A [`RequestCaller`] wrapper that coalesces concurrent identical remote
+/// fetches through a [`SingleFlight`] registry keyed by a caller-supplied key.
+///
+/// When many requests map to the same key while a fetch is already in flight,
+/// only the first performs the remote call; the rest await a clone of its
+/// result. This collapses a thundering herd of cache misses for a popular
+/// resource into a single origin request. Wrap the inner caller and pass the
+/// result to `handle_response_caching` in place of the bare caller.
+///
+/// Because the shared result must be cloned to every waiter, the wrapper
+/// materializes the response body eagerly (into an owned [`HTTPResponse`])
+/// rather than leaving it streaming. The key closure must distinguish requests
+/// that would produce different responses — notably a conditional revalidation
+/// from an unconditional fetch — so a coalesced caller never receives another
+/// request's response.
+pub struct CoalescingRequestCaller<Caller, KeyFn>
+where
+    Caller: RequestCaller,
+    Caller::Headers: Clone + Send + Sync + 'static,
+{
+    inner: Caller,
+    key_fn: KeyFn,
+    in_flight: SingleFlight<Result<HTTPResponse<Caller::Headers>>>,
+}
+
+impl<Caller, KeyFn> CoalescingRequestCaller<Caller, KeyFn>
+where
+    Caller: RequestCaller,
+    Caller::Headers: Clone + Send + Sync + 'static,
+    KeyFn: Fn(&HTTPRequest<Caller::Headers>) -> String + Send + Sync,
+{
+    /// Wrap `inner`, coalescing fetches that share the key produced by `key_fn`.
+    pub fn new(inner: Caller, key_fn: KeyFn) -> Self {
+        Self {
+            inner,
+            key_fn,
+            in_flight: SingleFlight::new(),
+        }
+    }
+}
+
+impl<Caller, KeyFn> RequestCaller for CoalescingRequestCaller<Caller, KeyFn>
+where
+    Caller: RequestCaller + Clone + 'static,
+    Caller::Headers: Clone + Send + Sync + 'static,
+    KeyFn: Fn(&HTTPRequest<Caller::Headers>) -> String + Send + Sync,
+{
+    type Headers = Caller::Headers;
+    type Response = HTTPResponse<Caller::Headers>;
+
+    fn read_remote_headers(
+        &self,
+        request: &HTTPRequest<Self::Headers>,
+    ) -> impl core::future::Future<Output = Result<Self::Response>> + Send + Sync {
+        let key = (self.key_fn)(request);
+        // The shared future outlives this call's borrow of `&self`, so it owns
+        // an inner caller clone and the request rather than borrowing them.
+        let caller = self.inner.clone();
+        let request = request.clone();
+        async move {
+            self.in_flight
+                .run(&key, move || async move {
+                    let remote_response = caller.read_remote_headers(&request).await?;
+                    Ok(HTTPResponse {
+                        version: remote_response.version(),
+                        status: remote_response.status(),
+                        reason: remote_response.reason(),
+                        url: remote_response.url(),
+                        headers: remote_response.headers().clone(),
+                        body: remote_response.body().await?,
+                    })
+                })
+                .await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::http::{HttpMethod, HttpVersion};
+    use futures::executor::block_on;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    type Headers = HashMap<String, Vec<String>>;
+
+    #[derive(Clone)]
+    struct CountingCaller {
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl RequestCaller for CountingCaller {
+        type Headers = Headers;
+        type Response = HTTPResponse<Headers>;
+
+        fn read_remote_headers(
+            &self,
+            request: &HTTPRequest<Headers>,
+        ) -> impl core::future::Future<Output = Result<Self::Response>> + Send + Sync {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let url = request.url.clone();
+            async move {
+                Ok(HTTPResponse {
+                    version: HttpVersion::Http11,
+                    url,
+                    status: 200,
+                    reason: "OK".to_owned(),
+                    headers: HashMap::new(),
+                    body: b"ok".to_vec(),
+                })
+            }
+        }
+    }
+
+    #[test]
+    fn coalesces_concurrent_calls_for_same_key() {
+        let flight: SingleFlight<u32> = SingleFlight::new();
+        let calls = AtomicUsize::new(0);
+        let (tx, rx) = futures::channel::oneshot::channel::<()>();
+
+        // The leader blocks on `rx` so the follower is guaranteed to join the
+        // in-flight entry before the leader resolves.
+        let leader = flight.run("key", || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                let _ = rx.await;
+                42
+            }
+        });
+        let follower = flight.run("key", || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async { 0 }
+        });
+        let trigger = async {
+            tx.send(()).unwrap();
+        };
+
+        let (leader, follower, ()) =
+            block_on(async { futures::join!(leader, follower, trigger) });
+
+        assert_eq!(leader, 42);
+        assert_eq!(follower, 42, "follower received the leader's result");
+        assert_eq!(calls.load(Ordering::SeqCst), 1, "only the leader fetched");
+    }
+
+    #[test]
+    fn wrapper_delegates_to_inner_caller() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let coalescing = CoalescingRequestCaller::new(
+            CountingCaller {
+                calls: calls.clone(),
+            },
+            |request: &HTTPRequest<Headers>| request.url.to_string(),
+        );
+        let request = HTTPRequest {
+            method: HttpMethod::Get,
+            url: url::Url::parse("https://example.com/resource").unwrap(),
+            headers: HashMap::new(),
+            body: Vec::new(),
+        };
+
+        let response =
+            block_on(coalescing.read_remote_headers(&request)).unwrap();
+
+        assert_eq!(response.status, 200);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -1,12 +1,15 @@
 use super::{
-    error::Result,
+    error::{Error, Result},
     http::{HTTPRequest, HTTPResponse},
 };
 use std::borrow::Cow;
 
+pub mod backends;
+
 pub type CacheTimestamp = chrono::DateTime<chrono::Utc>;
 
 /// Data to be stored in cache
+#[derive(Clone)]
 pub struct CacheData {
     /// Timestamp when call has been recorded
     pub call_timestamp: CacheTimestamp,
@@ -20,6 +23,15 @@ pub struct CacheData {
 
     /// HTTP Response data
     pub http_response: HTTPResponse,
+
+    /// Request header field names this response varies on (from `Vary`),
+    /// normalized to lower-case. Used to pick the matching content-negotiated
+    /// variant on lookup. Empty when the response does not vary.
+    pub vary: Vec<String>,
+
+    /// Cache tags this entry belongs to, used for bulk invalidation via
+    /// [`CacheManager::evict_by_tag`]. Empty when the entry carries no tags.
+    pub tags: Vec<String>,
 }
 
 /// A trait providing methods for storing, reading, and removing cache records.
@@ -33,4 +45,14 @@ pub trait CacheManager: Send + Sync + 'static {
 
     /// Attempt to remove a record from cache.
     async fn delete(&self, cache_key: &String) -> Result<Option<CacheData>>;
+
+    /// Evict every record carrying the given cache tag, returning the number of
+    /// records removed.
+    ///
+    /// The default implementation reports the operation as unsupported; backends
+    /// that track tags (such as the built-in in-memory and on-disk managers)
+    /// override it.
+    async fn evict_by_tag(&self, _tag: &str) -> Result<usize> {
+        Err(Error::FIXME)
+    }
 }
@@ -0,0 +1,400 @@
+//! Ready-made [`CacheManager`] implementations.
+//!
+//! Both backends treat an entry whose `expiration_time` has passed as absent
+//! on [`CacheManager::get`], so a stale record is never served even if a
+//! caching config would have kept it.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use super::{CacheData, CacheManager, CacheTimestamp};
+use crate::core::error::{Error, Result};
+use crate::core::http::{HTTPRequest, HTTPResponse};
+
+/// In-memory [`CacheManager`] backed by a concurrent map keyed by the cache
+/// key string.
+///
+/// An optional `max_entries` bound enables LRU eviction: once the map is full,
+/// inserting a new key evicts the least-recently-used entry.
+pub struct InMemoryCacheManager {
+    entries: Mutex<HashMap<String, Entry>>,
+    max_entries: Option<usize>,
+    clock: Mutex<u64>,
+}
+
+/// A stored record together with its last-use tick for LRU accounting.
+struct Entry {
+    data: CacheData,
+    last_used: u64,
+}
+
+impl InMemoryCacheManager {
+    /// Create an unbounded in-memory cache.
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            max_entries: None,
+            clock: Mutex::new(0),
+        }
+    }
+
+    /// Create an in-memory cache that keeps at most `max_entries` records,
+    /// evicting the least-recently-used one when full.
+    pub fn with_capacity(max_entries: usize) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            max_entries: Some(max_entries),
+            clock: Mutex::new(0),
+        }
+    }
+
+    fn next_tick(&self) -> u64 {
+        let mut clock = self.clock.lock().unwrap();
+        *clock += 1;
+        *clock
+    }
+}
+
+impl Default for InMemoryCacheManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CacheManager for InMemoryCacheManager {
+    async fn get(&self, cache_key: &String) -> Result<Option<CacheData>> {
+        let now = chrono::offset::Utc::now();
+        let mut entries = self.entries.lock().unwrap();
+        let Some(entry) = entries.get_mut(cache_key) else {
+            return Ok(None);
+        };
+        if is_expired(&entry.data.expiration_time, &now) {
+            entries.remove(cache_key);
+            return Ok(None);
+        }
+        entry.last_used = {
+            let mut clock = self.clock.lock().unwrap();
+            *clock += 1;
+            *clock
+        };
+        Ok(Some(entry.data.clone()))
+    }
+
+    async fn put(&self, cache_key: &String, data: &CacheData) -> Result<()> {
+        let tick = self.next_tick();
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(max_entries) = self.max_entries {
+            if !entries.contains_key(cache_key) && entries.len() >= max_entries {
+                if let Some(victim) = entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.last_used)
+                    .map(|(key, _)| key.clone())
+                {
+                    entries.remove(&victim);
+                }
+            }
+        }
+        entries.insert(
+            cache_key.clone(),
+            Entry {
+                data: data.clone(),
+                last_used: tick,
+            },
+        );
+        Ok(())
+    }
+
+    async fn delete(&self, cache_key: &String) -> Result<Option<CacheData>> {
+        let mut entries = self.entries.lock().unwrap();
+        Ok(entries.remove(cache_key).map(|entry| entry.data))
+    }
+
+    async fn evict_by_tag(&self, tag: &str) -> Result<usize> {
+        let mut entries = self.entries.lock().unwrap();
+        let before = entries.len();
+        entries.retain(|_, entry| !entry.data.tags.iter().any(|stored| stored == tag));
+        Ok(before - entries.len())
+    }
+}
+
+/// On-disk [`CacheManager`] that serializes records to a directory.
+///
+/// Each entry lives under a content-addressed directory derived from the cache
+/// key. Metadata (request, response head, timestamps) is written to `meta.json`
+/// and the response body to `body.bin`, so large bodies stay out of the
+/// metadata blob and can be streamed independently.
+pub struct DiskCacheManager {
+    root: PathBuf,
+}
+
+/// Serializable metadata written alongside the body blob.
+#[derive(Serialize, Deserialize)]
+struct StoredMeta {
+    call_timestamp: CacheTimestamp,
+    expiration_time: Option<CacheTimestamp>,
+    http_request: HTTPRequest,
+    /// Response with an empty body; the bytes live in `body.bin`.
+    http_response: HTTPResponse,
+    #[serde(default)]
+    vary: Vec<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+impl DiskCacheManager {
+    /// Create a disk cache rooted at `root`, creating the directory if needed.
+    pub fn new(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        std::fs::create_dir_all(&root).map_err(|_| Error::FIXME)?;
+        Ok(Self { root })
+    }
+
+    /// Directory holding the record for `cache_key`.
+    fn entry_dir(&self, cache_key: &str) -> PathBuf {
+        let mut hasher = DefaultHasher::new();
+        cache_key.hash(&mut hasher);
+        let digest = format!("{:016x}", hasher.finish());
+        self.root.join(&digest[..2]).join(&digest[2..])
+    }
+}
+
+impl CacheManager for DiskCacheManager {
+    async fn get(&self, cache_key: &String) -> Result<Option<CacheData>> {
+        let dir = self.entry_dir(cache_key);
+        let meta_path = dir.join("meta.json");
+        if !meta_path.exists() {
+            return Ok(None);
+        }
+
+        let meta_bytes = std::fs::read(&meta_path).map_err(|_| Error::FIXME)?;
+        let meta: StoredMeta = serde_json::from_slice(&meta_bytes).map_err(|_| Error::FIXME)?;
+
+        let now = chrono::offset::Utc::now();
+        if is_expired(&meta.expiration_time, &now) {
+            // Lazily drop expired records so they don't linger on disk.
+            let _ = std::fs::remove_dir_all(&dir);
+            return Ok(None);
+        }
+
+        let body = std::fs::read(dir.join("body.bin")).unwrap_or_default();
+        let http_response = HTTPResponse {
+            body,
+            ..meta.http_response
+        };
+        Ok(Some(CacheData {
+            call_timestamp: meta.call_timestamp,
+            expiration_time: meta.expiration_time,
+            http_request: meta.http_request,
+            http_response,
+            vary: meta.vary,
+            tags: meta.tags,
+        }))
+    }
+
+    async fn put(&self, cache_key: &String, data: &CacheData) -> Result<()> {
+        let dir = self.entry_dir(cache_key);
+        std::fs::create_dir_all(&dir).map_err(|_| Error::FIXME)?;
+
+        let head = HTTPResponse {
+            body: Vec::new(),
+            ..data.http_response.clone()
+        };
+        let meta = StoredMeta {
+            call_timestamp: data.call_timestamp,
+            expiration_time: data.expiration_time,
+            http_request: data.http_request.clone(),
+            http_response: head,
+            vary: data.vary.clone(),
+            tags: data.tags.clone(),
+        };
+        let meta_bytes = serde_json::to_vec(&meta).map_err(|_| Error::FIXME)?;
+
+        write_atomic(&dir.join("body.bin"), &data.http_response.body)?;
+        write_atomic(&dir.join("meta.json"), &meta_bytes)?;
+        Ok(())
+    }
+
+    async fn delete(&self, cache_key: &String) -> Result<Option<CacheData>> {
+        let existing = self.get(cache_key).await?;
+        if existing.is_some() {
+            let _ = std::fs::remove_dir_all(self.entry_dir(cache_key));
+        }
+        Ok(existing)
+    }
+
+    async fn evict_by_tag(&self, tag: &str) -> Result<usize> {
+        let mut removed = 0;
+        let Ok(shards) = std::fs::read_dir(&self.root) else {
+            return Ok(0);
+        };
+        for shard in shards.flatten() {
+            let Ok(entries) = std::fs::read_dir(shard.path()) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let dir = entry.path();
+                let Ok(bytes) = std::fs::read(dir.join("meta.json")) else {
+                    continue;
+                };
+                let Ok(meta) = serde_json::from_slice::<StoredMeta>(&bytes) else {
+                    continue;
+                };
+                if meta.tags.iter().any(|stored| stored == tag)
+                    && std::fs::remove_dir_all(&dir).is_ok()
+                {
+                    removed += 1;
+                }
+            }
+        }
+        Ok(removed)
+    }
+}
+
+/// Whether an entry with the given expiration is expired at `now`.
+fn is_expired(expiration_time: &Option<CacheTimestamp>, now: &CacheTimestamp) -> bool {
+    matches!(expiration_time, Some(expires) if expires <= now)
+}
+
+/// Write `bytes` to `path` via a temporary file and rename, so a reader never
+/// observes a half-written blob.
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, bytes).map_err(|_| Error::FIXME)?;
+    std::fs::rename(&tmp, path).map_err(|_| Error::FIXME)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::cache::CacheData;
+    use crate::core::http::{HttpMethod, HttpVersion};
+    use futures::executor::block_on;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    fn entry(tags: &[&str], expiration_time: Option<CacheTimestamp>) -> CacheData {
+        let url = url::Url::parse("https://example.com/resource").unwrap();
+        CacheData {
+            call_timestamp: chrono::offset::Utc::now(),
+            expiration_time,
+            http_request: HTTPRequest {
+                method: HttpMethod::Get,
+                url: url.clone(),
+                headers: HashMap::new(),
+                body: Vec::new(),
+            },
+            http_response: HTTPResponse {
+                version: HttpVersion::Http11,
+                url,
+                status: 200,
+                reason: "OK".to_owned(),
+                headers: HashMap::new(),
+                body: b"payload".to_vec(),
+            },
+            vary: Vec::new(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        }
+    }
+
+    fn scratch_dir() -> PathBuf {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let unique = format!(
+            "http-caching-test-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::SeqCst)
+        );
+        std::env::temp_dir().join(unique)
+    }
+
+    #[test]
+    fn in_memory_round_trip() {
+        let cache = InMemoryCacheManager::new();
+        let key = "k".to_owned();
+        block_on(cache.put(&key, &entry(&[], None))).unwrap();
+        let stored = block_on(cache.get(&key)).unwrap().expect("entry present");
+        assert_eq!(stored.http_response.body, b"payload");
+    }
+
+    #[test]
+    fn in_memory_treats_expired_entry_as_absent() {
+        let cache = InMemoryCacheManager::new();
+        let key = "k".to_owned();
+        let past = chrono::offset::Utc::now() - chrono::Duration::seconds(10);
+        block_on(cache.put(&key, &entry(&[], Some(past)))).unwrap();
+        assert!(block_on(cache.get(&key)).unwrap().is_none());
+    }
+
+    #[test]
+    fn in_memory_evicts_least_recently_used() {
+        let cache = InMemoryCacheManager::with_capacity(2);
+        for key in ["a", "b"] {
+            block_on(cache.put(&key.to_owned(), &entry(&[], None))).unwrap();
+        }
+        // Touch `a` so `b` becomes the least-recently-used victim.
+        block_on(cache.get(&"a".to_owned())).unwrap();
+        block_on(cache.put(&"c".to_owned(), &entry(&[], None))).unwrap();
+
+        assert!(block_on(cache.get(&"a".to_owned())).unwrap().is_some());
+        assert!(block_on(cache.get(&"b".to_owned())).unwrap().is_none());
+        assert!(block_on(cache.get(&"c".to_owned())).unwrap().is_some());
+    }
+
+    #[test]
+    fn in_memory_evicts_by_tag() {
+        let cache = InMemoryCacheManager::new();
+        block_on(cache.put(&"a".to_owned(), &entry(&["news"], None))).unwrap();
+        block_on(cache.put(&"b".to_owned(), &entry(&["news", "sports"], None))).unwrap();
+        block_on(cache.put(&"c".to_owned(), &entry(&["sports"], None))).unwrap();
+
+        assert_eq!(block_on(cache.evict_by_tag("news")).unwrap(), 2);
+        assert!(block_on(cache.get(&"a".to_owned())).unwrap().is_none());
+        assert!(block_on(cache.get(&"c".to_owned())).unwrap().is_some());
+    }
+
+    #[test]
+    fn disk_round_trip_keeps_body_and_head_separate() {
+        let root = scratch_dir();
+        let cache = DiskCacheManager::new(&root).unwrap();
+        let key = "k".to_owned();
+        block_on(cache.put(&key, &entry(&[], None))).unwrap();
+
+        let stored = block_on(cache.get(&key)).unwrap().expect("entry present");
+        assert_eq!(stored.http_response.body, b"payload");
+        assert_eq!(stored.http_response.status, 200);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn disk_drops_expired_entry_on_get() {
+        let root = scratch_dir();
+        let cache = DiskCacheManager::new(&root).unwrap();
+        let key = "k".to_owned();
+        let past = chrono::offset::Utc::now() - chrono::Duration::seconds(10);
+        block_on(cache.put(&key, &entry(&[], Some(past)))).unwrap();
+
+        assert!(block_on(cache.get(&key)).unwrap().is_none());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn disk_deletes_and_evicts_by_tag() {
+        let root = scratch_dir();
+        let cache = DiskCacheManager::new(&root).unwrap();
+        block_on(cache.put(&"a".to_owned(), &entry(&["news"], None))).unwrap();
+        block_on(cache.put(&"b".to_owned(), &entry(&["news"], None))).unwrap();
+
+        assert!(block_on(cache.delete(&"a".to_owned())).unwrap().is_some());
+        assert!(block_on(cache.get(&"a".to_owned())).unwrap().is_none());
+        assert_eq!(block_on(cache.evict_by_tag("news")).unwrap(), 1);
+        assert!(block_on(cache.get(&"b".to_owned())).unwrap().is_none());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -1,5 +1,6 @@
 use super::cache::CacheTimestamp;
 use super::http::{HTTPRequest, HTTPResponse};
+use std::collections::HashMap;
 use std::sync::Arc;
 
 /// Policy how response needs to be cached
@@ -74,6 +75,18 @@ pub type CacheResponsePolicyFn<AdditionalParams> = Arc<
     dyn Fn(&HTTPRequest, &HTTPResponse, &AdditionalParams) -> CacheResponsePolicy + Send + Sync,
 >;
 
+/// Assign cache tags to a response so groups of entries can be invalidated at
+/// once via [`crate::cache::CacheManager::evict_by_tag`].
+///
+/// NOTE: Function is called on every request that stores a response: on a cache
+/// miss or update, and after a `304 Not Modified` revalidation. It receives the
+/// same HTTP response as [`CacheResponsePolicyFn`].
+///
+/// A closure that takes [`crate::http::HTTPRequest`], [`crate::http::HTTPResponse`]
+/// and returns the list of tags the entry belongs to.
+pub type CacheTagsFn<AdditionalParams> =
+    Arc<dyn Fn(&HTTPRequest, &HTTPResponse, &AdditionalParams) -> Vec<String> + Send + Sync>;
+
 /// Additional cache configuration
 pub struct CacheConfig<AdditionalParams> {
     /// Generate key based on HTTP given request. CacheKey::NoKey by default.
@@ -101,4 +114,456 @@ pub struct CacheConfig<AdditionalParams> {
     ///
     /// NOTE: Function is called on every request, only on cache miss.
     pub cache_policy_fn: Option<CacheResponsePolicyFn<AdditionalParams>>,
+
+    /// Assign cache tags to a stored response for bulk invalidation. No tags by
+    /// default.
+    ///
+    /// NOTE: Function is called on every request that stores a response: on a
+    /// cache miss or update, and after a `304 Not Modified` revalidation.
+    pub cache_tags_fn: Option<CacheTagsFn<AdditionalParams>>,
+
+    /// Whether heuristic freshness (from `Last-Modified`) may be assigned to
+    /// responses carrying no explicit freshness directive, in the policy built
+    /// by [`Self::semantic_policy`]. `CacheConfig` has no default value;
+    /// [`SemanticPolicy::default`] leaves heuristic caching off.
+    pub heuristic_caching: bool,
+
+    /// Upper bound on a heuristic freshness lifetime, in the policy built by
+    /// [`Self::semantic_policy`]. [`SemanticPolicy::default`] uses 24h.
+    pub heuristic_max_lifetime: chrono::Duration,
+}
+
+impl<AdditionalParams> CacheConfig<AdditionalParams> {
+    /// Derive a [`SemanticPolicy`] from this config's heuristic settings.
+    pub fn semantic_policy(&self, shared: bool) -> SemanticPolicy {
+        SemanticPolicy {
+            shared,
+            heuristic: self.heuristic_caching,
+            heuristic_max_lifetime: self.heuristic_max_lifetime,
+        }
+    }
+}
+
+/// Build a [`CacheKeepFn`] that keeps an entry while fresh and requests
+/// revalidation (`Update`) once it expires.
+///
+/// An entry is treated as expired when its expiration timestamp is at or before
+/// "now" (`expires <= now`), so an entry expiring exactly now is not served.
+pub fn semantic_cache_keep_fn<AdditionalParams>() -> CacheKeepFn<AdditionalParams>
+where
+    AdditionalParams: Send + Sync + 'static,
+{
+    Arc::new(
+        |_request, _response, expiration_time, _additional_params| match expiration_time {
+            None => CacheKeep::Keep,
+            Some(expires) if chrono::offset::Utc::now() < *expires => CacheKeep::Keep,
+            Some(_) => CacheKeep::Update,
+        },
+    )
+}
+
+/// Standards-compliant (RFC 7234) freshness engine driving a
+/// [`CacheResponsePolicyFn`], so users get HTTP-semantic caching without
+/// re-implementing the algorithm.
+#[derive(Debug, Clone, Copy)]
+pub struct SemanticPolicy {
+    /// Shared (proxy) cache: honors `s-maxage` and refuses `private` responses.
+    pub shared: bool,
+
+    /// Whether to assign a heuristic freshness lifetime (from `Last-Modified`)
+    /// to responses that carry no explicit freshness directive.
+    pub heuristic: bool,
+
+    /// Upper bound on a heuristic freshness lifetime.
+    pub heuristic_max_lifetime: chrono::Duration,
+}
+
+impl Default for SemanticPolicy {
+    fn default() -> Self {
+        Self {
+            shared: false,
+            heuristic: false,
+            heuristic_max_lifetime: chrono::Duration::hours(24),
+        }
+    }
+}
+
+impl SemanticPolicy {
+    /// Compute the [`CacheResponsePolicy`] for a response from its
+    /// `Cache-Control`, `Expires`, `Age` and `Date` headers.
+    ///
+    /// Freshness lifetime is taken, in priority order, from `s-maxage` (shared
+    /// caches only), `max-age`, then `Expires - Date`. The current age is the
+    /// `Age` header plus the delay since `Date`, and the resulting expiration
+    /// date is `now + max(0, freshness_lifetime - current_age)`. With heuristic
+    /// caching enabled, a response lacking explicit freshness but carrying
+    /// `Last-Modified` is assigned `(now - Last-Modified) / 10`, clamped to
+    /// [`Self::heuristic_max_lifetime`].
+    pub fn policy(&self, response: &HTTPResponse) -> CacheResponsePolicy {
+        let cache_control = parse_cache_control(&response.headers);
+
+        if cache_control.contains_key("no-store")
+            || (self.shared && cache_control.contains_key("private"))
+        {
+            return CacheResponsePolicy::NoCache;
+        }
+        if cache_control.contains_key("immutable") {
+            return CacheResponsePolicy::CacheWithoutExpirationDate;
+        }
+
+        let now = chrono::offset::Utc::now();
+        let date = header_value(&response.headers, "date").and_then(parse_http_date);
+        let current_age = current_age(&response.headers, date, now);
+
+        if let Some(lifetime) = freshness_lifetime(&cache_control, &response.headers, date, self.shared)
+        {
+            let remaining = (lifetime - current_age).max(0);
+            return CacheResponsePolicy::CacheWithExpirationDate(
+                now + chrono::Duration::seconds(remaining),
+            );
+        }
+
+        if self.heuristic {
+            if let Some(expiration) = self.heuristic_expiration(&response.headers, now) {
+                return CacheResponsePolicy::CacheWithExpirationDate(expiration);
+            }
+        }
+
+        // No explicit freshness and no heuristic lifetime: the response may be
+        // stored (so its validators can drive conditional revalidation) but must
+        // never be served without first checking with the origin. Treating it as
+        // already-expired makes `semantic_cache_keep_fn` return `Update` on every
+        // hit, so a directive-less response is never cached fresh-forever.
+        CacheResponsePolicy::CacheWithExpirationDate(now)
+    }
+
+    /// Build a [`CacheResponsePolicyFn`] from this policy.
+    pub fn into_fn<AdditionalParams>(self) -> CacheResponsePolicyFn<AdditionalParams>
+    where
+        AdditionalParams: Send + Sync + 'static,
+    {
+        Arc::new(move |_request, response, _additional_params| self.policy(response))
+    }
+
+    /// Heuristic freshness lifetime from `Last-Modified`, clamped to
+    /// [`Self::heuristic_max_lifetime`].
+    fn heuristic_expiration(
+        &self,
+        headers: &HashMap<String, Vec<String>>,
+        now: CacheTimestamp,
+    ) -> Option<CacheTimestamp> {
+        let last_modified = header_value(headers, "last-modified").and_then(parse_http_date)?;
+        let age = (now - last_modified).num_seconds();
+        if age <= 0 {
+            return None;
+        }
+        let lifetime = chrono::Duration::seconds(age / 10).min(self.heuristic_max_lifetime);
+        Some(now + lifetime)
+    }
+}
+
+/// Build a [`CacheResponsePolicyFn`] that derives freshness from the standard
+/// HTTP caching headers (RFC 7234).
+///
+/// Pass `shared = true` for a shared (proxy) cache. This helper disables
+/// heuristic caching; use [`SemanticPolicy`] directly to enable it.
+pub fn semantic_cache_policy_fn<AdditionalParams>(
+    shared: bool,
+) -> CacheResponsePolicyFn<AdditionalParams>
+where
+    AdditionalParams: Send + Sync + 'static,
+{
+    SemanticPolicy {
+        shared,
+        ..SemanticPolicy::default()
+    }
+    .into_fn()
+}
+
+/// Compute the [`CacheResponsePolicy`] for a response, without heuristic caching.
+pub fn semantic_cache_policy(response: &HTTPResponse, shared: bool) -> CacheResponsePolicy {
+    SemanticPolicy {
+        shared,
+        ..SemanticPolicy::default()
+    }
+    .policy(response)
+}
+
+/// Build a [`CacheTagsFn`] that reads cache tags from the response's
+/// `Cache-Tag` and `Surrogate-Key` headers, as used by common CDNs.
+pub fn semantic_cache_tags_fn<AdditionalParams>() -> CacheTagsFn<AdditionalParams>
+where
+    AdditionalParams: Send + Sync + 'static,
+{
+    Arc::new(|_request, response, _additional_params| parse_tags(&response.headers))
+}
+
+/// Parse the `Cache-Tag` and `Surrogate-Key` headers into a de-duplicated list
+/// of tags. `Cache-Tag` is comma-separated; `Surrogate-Key` is space-separated.
+pub fn parse_tags(headers: &HashMap<String, Vec<String>>) -> Vec<String> {
+    let mut tags = Vec::new();
+    if let Some(value) = header_value(headers, "cache-tag") {
+        tags.extend(value.split(',').map(str::trim).map(str::to_owned));
+    }
+    if let Some(value) = header_value(headers, "surrogate-key") {
+        tags.extend(value.split_whitespace().map(str::to_owned));
+    }
+    tags.retain(|tag| !tag.is_empty());
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+/// Current age of the response, in seconds: the `Age` header plus the elapsed
+/// time since the origin `Date`.
+fn current_age(
+    headers: &HashMap<String, Vec<String>>,
+    date: Option<CacheTimestamp>,
+    now: CacheTimestamp,
+) -> i64 {
+    let age_value = header_value(headers, "age")
+        .and_then(|value| value.trim().parse::<i64>().ok())
+        .unwrap_or(0);
+    let apparent_age = date.map_or(0, |date| (now - date).num_seconds().max(0));
+    age_value + apparent_age
+}
+
+/// Freshness lifetime of the response in seconds, if it can be determined.
+fn freshness_lifetime(
+    cache_control: &HashMap<String, Option<String>>,
+    headers: &HashMap<String, Vec<String>>,
+    date: Option<CacheTimestamp>,
+    shared: bool,
+) -> Option<i64> {
+    if shared {
+        if let Some(s_maxage) = header_directive_seconds(cache_control, "s-maxage") {
+            return Some(s_maxage);
+        }
+    }
+    if let Some(max_age) = header_directive_seconds(cache_control, "max-age") {
+        return Some(max_age);
+    }
+    let expires = header_value(headers, "expires").and_then(parse_http_date)?;
+    Some((expires - date?).num_seconds())
+}
+
+/// Look up the first value of a header by its case-insensitive name.
+pub(crate) fn header_value<'headers>(
+    headers: &'headers HashMap<String, Vec<String>>,
+    name: &str,
+) -> Option<&'headers str> {
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .and_then(|(_, values)| values.first())
+        .map(String::as_str)
+}
+
+/// Parse a numeric `Cache-Control` directive (e.g. `max-age`) into seconds.
+fn header_directive_seconds(
+    cache_control: &HashMap<String, Option<String>>,
+    name: &str,
+) -> Option<i64> {
+    cache_control
+        .get(name)
+        .and_then(|value| value.as_deref())
+        .and_then(|value| value.parse::<i64>().ok())
+}
+
+/// Parse the `Cache-Control` header into a map of directive to optional value.
+/// Directive names are lower-cased; valueless directives map to `None`.
+pub(crate) fn parse_cache_control(
+    headers: &HashMap<String, Vec<String>>,
+) -> HashMap<String, Option<String>> {
+    let mut directives = HashMap::new();
+    let Some(value) = header_value(headers, "cache-control") else {
+        return directives;
+    };
+    for part in value.split(',') {
+        let part = part.trim();
+        if part.is_empty() {
+            continue;
+        }
+        let (name, value) = match part.split_once('=') {
+            Some((name, value)) => (name.trim(), Some(value.trim().trim_matches('"').to_owned())),
+            None => (part, None),
+        };
+        directives.insert(name.to_ascii_lowercase(), value);
+    }
+    directives
+}
+
+/// Parse the `Vary` header into a list of lower-cased field names. A `*` entry
+/// is returned as a single `"*"` element marking the response uncacheable.
+pub fn parse_vary(headers: &HashMap<String, Vec<String>>) -> Vec<String> {
+    let Some(value) = header_value(headers, "vary") else {
+        return Vec::new();
+    };
+    if value.split(',').any(|field| field.trim() == "*") {
+        return vec!["*".to_owned()];
+    }
+    value
+        .split(',')
+        .map(|field| field.trim().to_ascii_lowercase())
+        .filter(|field| !field.is_empty())
+        .collect()
+}
+
+/// Secondary cache key built from the values of the request headers named in
+/// `vary`.
+///
+/// Returns `None` when `vary` contains `*` (the response is uncacheable). Field
+/// names are matched case-insensitively and the pairs are sorted so the key is
+/// stable regardless of header ordering.
+pub fn vary_secondary_key(headers: &HashMap<String, Vec<String>>, vary: &[String]) -> Option<String> {
+    if vary.iter().any(|field| field == "*") {
+        return None;
+    }
+    let mut pairs: Vec<String> = vary
+        .iter()
+        .map(|field| {
+            let value = header_value(headers, field).unwrap_or_default();
+            format!("{field}={value}")
+        })
+        .collect();
+    pairs.sort();
+    Some(pairs.join("&"))
+}
+
+/// Parse an HTTP-date (RFC 1123 preferred form) into a UTC timestamp.
+pub(crate) fn parse_http_date(value: &str) -> Option<CacheTimestamp> {
+    chrono::NaiveDateTime::parse_from_str(value.trim(), "%a, %d %b %Y %H:%M:%S GMT")
+        .ok()
+        .map(|date_time| date_time.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::http::{HttpVersion, HTTPResponse};
+
+    fn response(headers: &[(&str, &str)]) -> HTTPResponse {
+        let headers = headers
+            .iter()
+            .map(|(name, value)| (name.to_string(), vec![value.to_string()]))
+            .collect();
+        HTTPResponse {
+            version: HttpVersion::Http11,
+            url: url::Url::parse("https://example.com/").unwrap(),
+            status: 200,
+            reason: "OK".to_owned(),
+            headers,
+            body: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn max_age_yields_expiration_in_the_future() {
+        let policy = SemanticPolicy::default();
+        let now = chrono::offset::Utc::now();
+        match policy.policy(&response(&[("Cache-Control", "max-age=600")])) {
+            CacheResponsePolicy::CacheWithExpirationDate(expires) => {
+                let remaining = (expires - now).num_seconds();
+                assert!((590..=600).contains(&remaining), "remaining={remaining}");
+            }
+            _ => panic!("expected an expiration date"),
+        }
+    }
+
+    #[test]
+    fn current_age_is_subtracted_from_freshness_lifetime() {
+        let policy = SemanticPolicy::default();
+        let now = chrono::offset::Utc::now();
+        match policy.policy(&response(&[("Cache-Control", "max-age=600"), ("Age", "100")])) {
+            CacheResponsePolicy::CacheWithExpirationDate(expires) => {
+                let remaining = (expires - now).num_seconds();
+                assert!((490..=500).contains(&remaining), "remaining={remaining}");
+            }
+            _ => panic!("expected an expiration date"),
+        }
+    }
+
+    #[test]
+    fn s_maxage_only_applies_to_shared_caches() {
+        let shared = SemanticPolicy {
+            shared: true,
+            ..SemanticPolicy::default()
+        };
+        let now = chrono::offset::Utc::now();
+        match shared.policy(&response(&[("Cache-Control", "s-maxage=1000, max-age=10")])) {
+            CacheResponsePolicy::CacheWithExpirationDate(expires) => {
+                let remaining = (expires - now).num_seconds();
+                assert!((990..=1000).contains(&remaining), "remaining={remaining}");
+            }
+            _ => panic!("expected an expiration date"),
+        }
+    }
+
+    #[test]
+    fn no_store_is_never_cached() {
+        let policy = SemanticPolicy::default();
+        assert!(matches!(
+            policy.policy(&response(&[("Cache-Control", "no-store")])),
+            CacheResponsePolicy::NoCache
+        ));
+    }
+
+    #[test]
+    fn private_refused_only_by_shared_cache() {
+        let private = response(&[("Cache-Control", "private, max-age=60")]);
+        let shared = SemanticPolicy {
+            shared: true,
+            ..SemanticPolicy::default()
+        };
+        assert!(matches!(
+            shared.policy(&private),
+            CacheResponsePolicy::NoCache
+        ));
+        assert!(matches!(
+            SemanticPolicy::default().policy(&private),
+            CacheResponsePolicy::CacheWithExpirationDate(_)
+        ));
+    }
+
+    #[test]
+    fn immutable_caches_without_expiration() {
+        let policy = SemanticPolicy::default();
+        assert!(matches!(
+            policy.policy(&response(&[("Cache-Control", "immutable")])),
+            CacheResponsePolicy::CacheWithoutExpirationDate
+        ));
+    }
+
+    #[test]
+    fn directiveless_response_is_not_fresh_forever() {
+        let policy = SemanticPolicy::default();
+        let now = chrono::offset::Utc::now();
+        // No freshness directive and no heuristic opt-in: the entry must be
+        // treated as already stale rather than cached indefinitely.
+        match policy.policy(&response(&[])) {
+            CacheResponsePolicy::CacheWithExpirationDate(expires) => {
+                assert!((expires - now).num_seconds().abs() <= 1);
+            }
+            _ => panic!("expected an already-expired entry"),
+        }
+    }
+
+    #[test]
+    fn heuristic_expiration_is_one_tenth_of_age() {
+        let policy = SemanticPolicy {
+            heuristic: true,
+            ..SemanticPolicy::default()
+        };
+        let now = chrono::offset::Utc::now();
+        let last_modified = (now - chrono::Duration::seconds(1000))
+            .format("%a, %d %b %Y %H:%M:%S GMT")
+            .to_string();
+        match policy.policy(&response(&[("Last-Modified", &last_modified)])) {
+            CacheResponsePolicy::CacheWithExpirationDate(expires) => {
+                let remaining = (expires - now).num_seconds();
+                assert!((95..=105).contains(&remaining), "remaining={remaining}");
+            }
+            _ => panic!("expected a heuristic expiration"),
+        }
+    }
 }
@@ -1,5 +1,9 @@
+use std::collections::HashMap;
+
 use super::cache::{CacheData, CacheManager};
-use super::cache_config::{self, CacheConfig, CacheKeep, CacheKeepFn, CacheResponsePolicy};
+use super::cache_config::{
+    self, header_value, CacheConfig, CacheKeep, CacheKeepFn, CacheResponsePolicy, CacheTagsFn,
+};
 use super::error::Result;
 use super::http::{HTTPRequest, HTTPResponse};
 
@@ -13,14 +17,86 @@ pub enum CacheHitResult {
     CacheHit,
     /// Cache hit, data has been updated from remote
     CacheUpdate,
+    /// Cache hit, a stale entry was revalidated via a `304 Not Modified` response
+    CacheRevalidated,
     /// Cache hit, cached data has been evicted, data has been retrieved from remote
     CacheEvict,
+    /// Cache miss under [`CacheMode::OnlyIfCached`]; the network was not used (504-style)
+    CacheGatewayTimeout,
+}
+
+/// Per-request override of the overall caching strategy, modelled on the
+/// request modes exposed by browser/fetch-style caches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CacheMode {
+    /// Consult the cache config and cache as usual.
+    #[default]
+    Default,
+    /// Bypass the cache entirely: never read and never write.
+    NoStore,
+    /// Always fetch from remote, but still store the result.
+    Reload,
+    /// Always revalidate a stored entry before serving it.
+    NoCache,
+    /// Serve any stored entry regardless of staleness; only fetch on a true miss.
+    ForceCache,
+    /// Serve the stored entry if present, otherwise fail without touching the network.
+    OnlyIfCached,
+}
+
+/// Conditional revalidation headers derived from a cached response's validators.
+pub struct ConditionalHeaders {
+    /// `If-None-Match` value taken from the stored `ETag`.
+    pub if_none_match: Option<String>,
+    /// `If-Modified-Since` value taken from the stored `Last-Modified`.
+    pub if_modified_since: Option<String>,
+}
+
+impl ConditionalHeaders {
+    /// Build validators from a stored response, or `None` if it carries neither
+    /// an `ETag` nor a `Last-Modified` header.
+    pub fn from_cached(response: &HTTPResponse) -> Option<Self> {
+        let if_none_match = header_value(&response.headers, "etag").map(str::to_owned);
+        let if_modified_since = header_value(&response.headers, "last-modified").map(str::to_owned);
+        if if_none_match.is_none() && if_modified_since.is_none() {
+            return None;
+        }
+        Some(Self {
+            if_none_match,
+            if_modified_since,
+        })
+    }
+
+    /// Attach the validators onto an outgoing request's headers.
+    pub fn apply(&self, headers: &mut HashMap<String, Vec<String>>) {
+        if let Some(etag) = &self.if_none_match {
+            headers.insert("If-None-Match".to_owned(), vec![etag.clone()]);
+        }
+        if let Some(last_modified) = &self.if_modified_since {
+            headers.insert("If-Modified-Since".to_owned(), vec![last_modified.clone()]);
+        }
+    }
 }
 
 /// Abstraction to do remote call for given request
 pub trait RequestCaller: Send + Sync + 'static {
     /// Call remote server to get actual HTTP response
     async fn read_remote_headers(&self, request: &HTTPRequest) -> Result<HTTPResponse>;
+
+    /// Re-fetch `request` conditionally to revalidate a stale cache entry.
+    ///
+    /// The default implementation attaches the given validators to the request
+    /// headers and delegates to [`Self::read_remote_headers`]; a server that
+    /// answers `304 Not Modified` lets the middleware keep the stored body.
+    async fn revalidate(
+        &self,
+        request: &HTTPRequest,
+        validators: &ConditionalHeaders,
+    ) -> Result<HTTPResponse> {
+        let mut conditional = request.clone();
+        validators.apply(&mut conditional.headers);
+        self.read_remote_headers(&conditional).await
+    }
 }
 
 pub trait Middleware: Send + Sync + 'static {
@@ -38,13 +114,25 @@ pub trait Middleware: Send + Sync + 'static {
 
     /// Handle request and return HTTP response with cache hit result
     ///
+    /// `cache_mode` selects the overall caching strategy for this single
+    /// request (see [`CacheMode`]); pass [`CacheMode::Default`] for the
+    /// config-driven behavior.
+    ///
     /// if response is None, then request hasn't been made
     async fn handle_request(
         &self,
         request: &HTTPRequest,
         remote_caller: &impl RequestCaller,
+        cache_mode: CacheMode,
     ) -> Result<(Option<HTTPResponse>, CacheHitResult)> {
         let cache_config = self.cache_config();
+
+        // `NoStore` never touches the cache in either direction.
+        if matches!(cache_mode, CacheMode::NoStore) {
+            let remote_response = remote_caller.read_remote_headers(request).await?;
+            return Ok((Some(remote_response), CacheHitResult::CacheOff));
+        }
+
         let Some(key_fn) = &cache_config.key_fn else {
             return Ok((None, CacheHitResult::CacheOff));
         };
@@ -58,7 +146,38 @@ pub trait Middleware: Send + Sync + 'static {
         let cache_manager = self.cache_manager();
 
         // TODO: proper error handling on await
-        let cache_data_opt = cache_manager.get(&cache_key).await?;
+        // The base key identifies *a* stored variant, which carries the
+        // response's `Vary` field names. When the response varies, re-key the
+        // lookup by this request's values for those fields so two
+        // content-negotiated variants of the same resource are served from
+        // their own entries instead of overwriting one another.
+        let cache_data_opt = match cache_manager.get(&cache_key).await? {
+            Some(probe) if !probe.vary.is_empty() => {
+                match cache_config::vary_secondary_key(&request.headers, &probe.vary) {
+                    // `Vary: *` marks the response uncacheable: never a hit.
+                    None => None,
+                    // The probe is already the variant this request asked for.
+                    Some(secondary)
+                        if cache_config::vary_secondary_key(
+                            &probe.http_request.headers,
+                            &probe.vary,
+                        )
+                        .as_deref()
+                            == Some(secondary.as_str()) =>
+                    {
+                        Some(probe)
+                    }
+                    // A different variant: look it up under its secondary key.
+                    Some(secondary) => {
+                        cache_manager
+                            .get(&vary_variant_key(&cache_key, &secondary))
+                            .await?
+                    }
+                }
+            }
+            other => other,
+        };
+
         let cache_keep = process_cache_hit::<Self::AdditionalParams>(
             request,
             &cache_data_opt,
@@ -66,24 +185,119 @@ pub trait Middleware: Send + Sync + 'static {
             &cache_config.cache_keep_fn,
         );
 
-        match cache_keep {
-            Some(CacheKeep::Keep) => {
-                return Ok((
-                    Some(cache_data_opt.unwrap().http_response),
-                    CacheHitResult::CacheHit,
-                ))
+        match cache_mode {
+            // Serve any stored entry regardless of staleness, only fetch on a miss.
+            CacheMode::ForceCache => {
+                if let Some(cache_data) = &cache_data_opt {
+                    return Ok((
+                        Some(cache_data.http_response.clone()),
+                        CacheHitResult::CacheHit,
+                    ));
+                }
             }
-            Some(CacheKeep::Evict) => {
-                cache_manager.delete(&cache_key);
-                return Ok((None, CacheHitResult::CacheEvict));
+            // Never hit the network: serve the stored entry or fail 504-style.
+            CacheMode::OnlyIfCached => {
+                return match &cache_data_opt {
+                    Some(cache_data) => Ok((
+                        Some(cache_data.http_response.clone()),
+                        CacheHitResult::CacheHit,
+                    )),
+                    None => Ok((None, CacheHitResult::CacheGatewayTimeout)),
+                };
             }
-            // no cached data or update
-            _ => {}
+            // Honour the config's keep policy.
+            CacheMode::Default => match cache_keep {
+                Some(CacheKeep::Keep) => {
+                    return Ok((
+                        Some(cache_data_opt.unwrap().http_response),
+                        CacheHitResult::CacheHit,
+                    ))
+                }
+                Some(CacheKeep::Evict) => {
+                    // TODO: proper error handling on await
+                    cache_manager.delete(&cache_key).await?;
+                    return Ok((None, CacheHitResult::CacheEvict));
+                }
+                // no cached data or update
+                _ => {}
+            },
+            // `Reload`/`NoCache` always go to the remote; `NoStore` handled above.
+            CacheMode::Reload | CacheMode::NoCache | CacheMode::NoStore => {}
         }
 
+        // A stale entry is revalidated conditionally; a true miss is fetched in
+        // full. Revalidation attaches `If-None-Match` / `If-Modified-Since` from
+        // the stored validators. `NoCache` forces revalidation of any stored
+        // entry, while `Reload` always performs an unconditional full fetch.
+        let revalidating = match cache_mode {
+            CacheMode::NoCache => cache_data_opt.is_some(),
+            CacheMode::Reload => false,
+            _ => matches!(cache_keep, Some(CacheKeep::Update)),
+        };
+        let validators = if revalidating {
+            cache_data_opt
+                .as_ref()
+                .and_then(|cache_data| ConditionalHeaders::from_cached(&cache_data.http_response))
+        } else {
+            None
+        };
+
         // Cache miss
         // TODO: proper error handling on await
-        let remote_response = remote_caller.read_remote_headers(request).await?;
+        let remote_response = match &validators {
+            Some(validators) => remote_caller.revalidate(request, validators).await?,
+            None => remote_caller.read_remote_headers(request).await?,
+        };
+
+        // Revalidation hit: reuse the stored body, refresh freshness metadata
+        // from the `304` headers and re-store the entry.
+        if validators.is_some() && remote_response.status == 304 {
+            let cache_data = cache_data_opt.unwrap();
+            let refreshed_response =
+                merge_not_modified(&cache_data.http_response, &remote_response);
+            let cache_policy = match &cache_config.cache_policy_fn {
+                None => CacheResponsePolicy::NoCache,
+                Some(cache_policy_fn) => {
+                    cache_policy_fn(request, &refreshed_response, additional_params)
+                }
+            };
+            let expiration_time = match cache_policy {
+                // The refreshed response must not be cached: evict the entry.
+                CacheResponsePolicy::NoCache => {
+                    // TODO: proper error handling on await
+                    cache_manager.delete(&cache_key).await?;
+                    return Ok((Some(refreshed_response), CacheHitResult::CacheRevalidated));
+                }
+                CacheResponsePolicy::CacheWithoutExpirationDate => None,
+                CacheResponsePolicy::CacheWithExpirationDate(expiration_date) => Some(expiration_date),
+            };
+            let refreshed = CacheData {
+                call_timestamp: chrono::offset::Utc::now(),
+                expiration_time,
+                http_request: request.clone(),
+                http_response: refreshed_response.clone(),
+                vary: cache_config::parse_vary(&refreshed_response.headers),
+                tags: cache_tags::<Self::AdditionalParams>(
+                    request,
+                    &refreshed_response,
+                    additional_params,
+                    &cache_config.cache_tags_fn,
+                ),
+            };
+            // TODO: proper error handling on await
+            cache_manager.put(&cache_key, &refreshed).await?;
+            if !refreshed.vary.is_empty() {
+                if let Some(secondary) =
+                    cache_config::vary_secondary_key(&request.headers, &refreshed.vary)
+                {
+                    cache_manager
+                        .put(&vary_variant_key(&cache_key, &secondary), &refreshed)
+                        .await?;
+                }
+            }
+            return Ok((Some(refreshed_response), CacheHitResult::CacheRevalidated));
+        }
+
         let cache_policy = match &cache_config.cache_policy_fn {
             None => CacheResponsePolicy::NoCache,
             Some(cache_policy_fn) => cache_policy_fn(request, &remote_response, additional_params),
@@ -101,10 +315,27 @@ pub trait Middleware: Send + Sync + 'static {
             expiration_time,
             http_request: request.clone(),
             http_response: remote_response.clone(),
+            vary: cache_config::parse_vary(&remote_response.headers),
+            tags: cache_tags::<Self::AdditionalParams>(
+                request,
+                &remote_response,
+                additional_params,
+                &cache_config.cache_tags_fn,
+            ),
         };
-        cache_manager.put(&cache_key, &new_cache_data);
+        // TODO: proper error handling on await
+        cache_manager.put(&cache_key, &new_cache_data).await?;
+        if !new_cache_data.vary.is_empty() {
+            if let Some(secondary) =
+                cache_config::vary_secondary_key(&request.headers, &new_cache_data.vary)
+            {
+                cache_manager
+                    .put(&vary_variant_key(&cache_key, &secondary), &new_cache_data)
+                    .await?;
+            }
+        }
 
-        let cache_hit_result = if matches!(cache_keep, Some(CacheKeep::Update)) {
+        let cache_hit_result = if cache_data_opt.is_some() {
             CacheHitResult::CacheUpdate
         } else {
             CacheHitResult::CacheMiss
@@ -113,6 +344,42 @@ pub trait Middleware: Send + Sync + 'static {
     }
 }
 
+/// Merge the headers carried by a `304 Not Modified` response onto the stored
+/// response. A `304` has no body, so the stored body is retained.
+fn merge_not_modified(stored: &HTTPResponse, not_modified: &HTTPResponse) -> HTTPResponse {
+    let mut refreshed = stored.clone();
+    for name in ["cache-control", "date", "expires", "etag", "last-modified"] {
+        if let Some(value) = header_value(&not_modified.headers, name) {
+            refreshed.headers.retain(|key, _| !key.eq_ignore_ascii_case(name));
+            refreshed.headers.insert(name.to_owned(), vec![value.to_owned()]);
+        }
+    }
+    refreshed
+}
+
+/// Compose the storage key for a content-negotiated variant from the base
+/// request key and the response's `Vary`-derived secondary key.
+///
+/// The separator is a control byte that cannot appear in a user-facing key, so
+/// a variant key never collides with a base key.
+fn vary_variant_key(cache_key: &str, secondary: &str) -> String {
+    format!("{cache_key}\u{1}{secondary}")
+}
+
+/// Compute the cache tags for a response to be stored, or an empty list when no
+/// tagging closure is configured.
+fn cache_tags<AdditionalParams>(
+    request: &HTTPRequest,
+    response: &HTTPResponse,
+    additional_params: &AdditionalParams,
+    cache_tags_fn: &Option<CacheTagsFn<AdditionalParams>>,
+) -> Vec<String> {
+    match cache_tags_fn {
+        None => Vec::new(),
+        Some(cache_tags_fn) => cache_tags_fn(request, response, additional_params),
+    }
+}
+
 fn process_cache_hit<AdditionalParams>(
     request: &HTTPRequest,
     cache_data_opt: &Option<CacheData>,
@@ -4,6 +4,7 @@ pub mod cache;
 pub mod cache_config;
 pub mod http;
 pub mod middleware;
+pub mod single_flight;
 
 pub use error::Error;
 pub use error::Result;
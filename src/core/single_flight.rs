@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use futures::future::{BoxFuture, FutureExt, Shared};
+
+use super::error::Result;
+use super::http::{HTTPRequest, HTTPResponse};
+use super::middleware::RequestCaller;
+
+/// Coalesces concurrent work that shares a cache key into a single execution.
+///
+/// When several callers map to the same key while a fetch is already running,
+/// only the first issues the remote call; the others await a shared clone of
+/// its result. This prevents a thundering herd of identical cache misses from
+/// each hitting the origin.
+///
+/// The pending future is registered *before* it is awaited, so callers that
+/// arrive while the body is still downloading join the in-flight call rather
+/// than starting their own. When the leader resolves, every waiter receives a
+/// clone of the result (wrap a non-cloneable result such as an error in an
+/// [`Arc`]), and the entry is cleared so later callers run afresh.
+pub struct SingleFlight<V>
+where
+    V: Clone + Send + 'static,
+{
+    in_flight: Arc<Mutex<HashMap<String, Shared<BoxFuture<'static, V>>>>>,
+}
+
+impl<V> SingleFlight<V>
+where
+    V: Clone + Send + 'static,
+{
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self {
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Run `make` under single-flight semantics for `key`.
+    ///
+    /// If a call for `key` is already in flight its result is shared; otherwise
+    /// `make` produces the future every concurrent caller awaits.
+    pub async fn run<MakeFut, Fut>(&self, key: &str, make: MakeFut) -> V
+    where
+        MakeFut: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = V> + Send + 'static,
+    {
+        let (shared, leader) = {
+            let mut in_flight = self.in_flight.lock().expect("single-flight registry poisoned");
+            match in_flight.get(key) {
+                Some(existing) => (existing.clone(), false),
+                None => {
+                    let shared = make().boxed().shared();
+                    in_flight.insert(key.to_owned(), shared.clone());
+                    (shared, true)
+                }
+            }
+        };
+
+        let value = shared.await;
+
+        // The leader that registered the entry clears it once resolved so later
+        // callers re-run rather than observe a completed future forever.
+        if leader {
+            self.in_flight
+                .lock()
+                .expect("single-flight registry poisoned")
+                .remove(key);
+        }
+
+        value
+    }
+}
+
+impl<V> Default for SingleFlight<V>
+where
+    V: Clone + Send + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A [`RequestCaller`] wrapper that coalesces concurrent identical remote
+/// fetches through a [`SingleFlight`] registry keyed by a caller-supplied key.
+///
+/// When many requests map to the same key while a fetch is already in flight,
+/// only the first performs the remote call; the rest await a clone of its
+/// result. This collapses a thundering herd of cache misses for a popular
+/// resource into a single origin request. Wrap the inner caller and hand the
+/// result to [`Middleware::handle_request`](super::middleware::Middleware::handle_request)
+/// in place of the bare caller.
+///
+/// The key closure must distinguish requests that would produce different
+/// responses — in particular a conditional revalidation (carrying
+/// `If-None-Match` / `If-Modified-Since`) from an unconditional fetch — so a
+/// coalesced caller never receives another request's response.
+pub struct CoalescingRequestCaller<Caller, KeyFn> {
+    inner: Caller,
+    key_fn: KeyFn,
+    in_flight: SingleFlight<Result<HTTPResponse>>,
+}
+
+impl<Caller, KeyFn> CoalescingRequestCaller<Caller, KeyFn>
+where
+    Caller: RequestCaller + Clone,
+    KeyFn: Fn(&HTTPRequest) -> String + Send + Sync + 'static,
+{
+    /// Wrap `inner`, coalescing fetches that share the key produced by `key_fn`.
+    pub fn new(inner: Caller, key_fn: KeyFn) -> Self {
+        Self {
+            inner,
+            key_fn,
+            in_flight: SingleFlight::new(),
+        }
+    }
+}
+
+impl<Caller, KeyFn> RequestCaller for CoalescingRequestCaller<Caller, KeyFn>
+where
+    Caller: RequestCaller + Clone,
+    KeyFn: Fn(&HTTPRequest) -> String + Send + Sync + 'static,
+{
+    async fn read_remote_headers(&self, request: &HTTPRequest) -> Result<HTTPResponse> {
+        let key = (self.key_fn)(request);
+        // Clone what the shared future must own: the leader's borrow of `&self`
+        // cannot outlive this call, so the future owns an inner caller and the
+        // request instead.
+        let caller = self.inner.clone();
+        let request = request.clone();
+        self.in_flight
+            .run(&key, move || async move {
+                caller.read_remote_headers(&request).await
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::http::{HttpMethod, HttpVersion};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn response(url: &url::Url) -> HTTPResponse {
+        HTTPResponse {
+            version: HttpVersion::Http11,
+            url: url.clone(),
+            status: 200,
+            reason: "OK".to_owned(),
+            headers: HashMap::new(),
+            body: b"ok".to_vec(),
+        }
+    }
+
+    #[derive(Clone)]
+    struct CountingCaller {
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl RequestCaller for CountingCaller {
+        async fn read_remote_headers(&self, request: &HTTPRequest) -> Result<HTTPResponse> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(response(&request.url))
+        }
+    }
+
+    #[test]
+    fn coalesces_concurrent_calls_for_same_key() {
+        let flight: SingleFlight<u32> = SingleFlight::new();
+        let calls = AtomicUsize::new(0);
+        let (tx, rx) = futures::channel::oneshot::channel::<()>();
+
+        // The leader blocks on `rx` so the follower is guaranteed to join the
+        // in-flight entry before the leader resolves.
+        let leader = flight.run("key", || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                let _ = rx.await;
+                42
+            }
+        });
+        let follower = flight.run("key", || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async { 0 }
+        });
+        let trigger = async {
+            tx.send(()).unwrap();
+        };
+
+        let (leader, follower, ()) =
+            futures::executor::block_on(async { futures::join!(leader, follower, trigger) });
+
+        assert_eq!(leader, 42);
+        assert_eq!(follower, 42, "follower received the leader's result");
+        assert_eq!(calls.load(Ordering::SeqCst), 1, "only the leader fetched");
+    }
+
+    #[test]
+    fn wrapper_delegates_to_inner_caller() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let coalescing = CoalescingRequestCaller::new(
+            CountingCaller {
+                calls: calls.clone(),
+            },
+            |request: &HTTPRequest| request.url.to_string(),
+        );
+        let request = HTTPRequest {
+            method: HttpMethod::Get,
+            url: url::Url::parse("https://example.com/resource").unwrap(),
+            headers: HashMap::new(),
+            body: Vec::new(),
+        };
+
+        let response =
+            futures::executor::block_on(coalescing.read_remote_headers(&request)).unwrap();
+
+        assert_eq!(response.status, 200);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}